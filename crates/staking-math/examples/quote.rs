@@ -0,0 +1,57 @@
+//! Quotes a stake position from fetched account data, the same numbers `view_position` returns.
+//!
+//! Save the raw data of a pool state and a stake entry, then pass both files:
+//!
+//! ```text
+//! solana account <POOL_STATE> --output-file pool.bin
+//! solana account <STAKE_ENTRY> --output-file entry.bin
+//! cargo run -p staking-math --example quote -- pool.bin entry.bin [unix_timestamp]
+//! ```
+//!
+//! Without a timestamp the quote is taken at the local clock.
+
+use staking_math::{PoolStateAccount, StakeEntryAccount, RATE_PRECISION};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// reward mint slots of the deployed program
+const MAX_REWARD_MINTS: usize = 4;
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!("usage: quote <pool state file> <stake entry file> [unix timestamp]");
+        std::process::exit(1);
+    }
+
+    let pool_data = std::fs::read(&args[1]).expect("can't read pool state file");
+    let entry_data = std::fs::read(&args[2]).expect("can't read stake entry file");
+    let now = match args.get(3) {
+        Some(timestamp) => timestamp.parse().expect("timestamp isn't a number"),
+        None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
+    };
+
+    let pool = PoolStateAccount::<MAX_REWARD_MINTS>::unpack(&pool_data)
+        .expect("not a pool state in the current layout");
+    let entry = StakeEntryAccount::<MAX_REWARD_MINTS>::unpack(&entry_data)
+        .expect("not a stake entry in the current layout");
+
+    let engine = pool.engine_at(now).expect("pool can't be quoted");
+    let mut position = entry.position();
+    let settled_balance = engine.settled_balance(&position).expect("position can't be quoted");
+    engine.settle_rewards(&mut position).expect("reward mints can't be quoted");
+
+    println!("principal:       {}", entry.balance);
+    println!("settled balance: {}", settled_balance);
+    if entry.balance != 0 {
+        let effective_rate = settled_balance as u128 * RATE_PRECISION / entry.balance as u128;
+        println!("effective rate:  {}", effective_rate);
+    }
+    for (reward_info, owed) in pool.reward_infos.iter().zip(position.rewards_owed.iter()) {
+        if reward_info.mint != [0; 32] {
+            println!("reward slot owed: {}", owed);
+        }
+    }
+    if entry.unbonding_amount != 0 {
+        println!("unbonding:       {} until {}", entry.unbonding_amount, entry.unbonding_end);
+    }
+}
\ No newline at end of file
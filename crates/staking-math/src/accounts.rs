@@ -0,0 +1,487 @@
+//! The program's `PoolState` and `StakeEntry` accounts as plain structs, and how they map onto
+//! the engine. The program goes through the same mapping, so a client that unpacks fetched
+//! account data quotes exactly what the program computes.
+
+use crate::{
+    pool::{Accumulator, Pool, Position},
+    MathError, MathResult,
+};
+
+/// First 8 bytes of every `PoolState` account
+pub const POOL_STATE_DISCRIMINATOR: [u8; 8] = [247, 237, 227, 245, 215, 195, 222, 70];
+/// First 8 bytes of every `StakeEntry` account
+pub const STAKE_ENTRY_DISCRIMINATOR: [u8; 8] = [187, 127, 9, 35, 155, 68, 86, 40];
+
+// bits of `PoolStateAccount::paused`, each one disables its instructions independently
+pub const PAUSE_STAKE: u8 = 1 << 0;
+pub const PAUSE_UNSTAKE: u8 = 1 << 1;
+pub const PAUSE_DISTRIBUTE: u8 = 1 << 2;
+pub const PAUSE_BURN: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_STAKE | PAUSE_UNSTAKE | PAUSE_DISTRIBUTE | PAUSE_BURN;
+
+/// One reward mint slot of a pool, in account order
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewardInfoAccount {
+    pub mint: [u8; 32],
+    pub vault: [u8; 32],
+    pub vault_bump: u8,
+    pub reward_rate: u128,
+    pub prev_scale_reward_rate: u128,
+    pub epoch_end_reward_rate: u128,
+}
+
+/// A `PoolState` account in field order, N is the number of reward mint slots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolStateAccount<const N: usize> {
+    pub authority: [u8; 32],
+    pub bump: u8,
+    pub amount: u64,
+    pub token_vault: [u8; 32],
+    pub token_mint: [u8; 32],
+    pub initialized_at: i64,
+    pub vault_bump: u8,
+    pub vault_auth_bump: u8,
+    pub vault_authority: [u8; 32],
+    pub distribution_rate: u128,
+    pub user_deposit_amt: u64,
+    pub pending_authority: [u8; 32],
+    pub emission_rate: u64,
+    pub emission_start: i64,
+    pub emission_end: i64,
+    pub last_emission_update: i64,
+    pub reward_infos: [RewardInfoAccount; N],
+    pub boost_rate: u128,
+    pub total_boost_weight: u128,
+    pub boost_reserve: u64,
+    pub unbonding_period: i64,
+    pub pending_withdrawal: u64,
+    pub rate_scale: u64,
+    pub prev_scale_boost_rate: u128,
+    pub epoch: u64,
+    pub epoch_end_rate_scale: u64,
+    pub epoch_end_boost_rate: u128,
+    pub deactivated: bool,
+    pub paused: u8,
+    pub distributor: [u8; 32],
+    pub burner: [u8; 32],
+    pub guardian: [u8; 32],
+    /// Settled reward mint earnings not claimed yet, per slot
+    pub rewards_owed_total: [u64; N],
+    /// Tells a pool apart from an earlier one closed at the same address
+    pub generation: u64,
+}
+
+/// A `StakeEntry` account in field order, N is the number of reward mint slots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakeEntryAccount<const N: usize> {
+    pub user: [u8; 32],
+    pub bump: u8,
+    pub balance: u64,
+    pub last_staked: i64,
+    pub initial_distribution_rate: u128,
+    pub reward_checkpoints: [u128; N],
+    pub rewards_owed: [u64; N],
+    pub lock_end: i64,
+    pub lock_weight_bps: u64,
+    pub boost_weight: u64,
+    pub boost_checkpoint: u128,
+    pub unbonding_amount: u64,
+    pub unbonding_end: i64,
+    pub initial_rate_scale: u64,
+    pub initial_epoch: u64,
+    pub position_mint: [u8; 32],
+    /// `PoolStateAccount::generation` of the pool the entry was opened in
+    pub pool_generation: u64,
+}
+
+/// Reads little-endian fields off the front of account data, the way borsh lays them out
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take<const L: usize>(&mut self) -> Option<[u8; L]> {
+        if self.data.len() < L {
+            return None;
+        }
+        let (head, rest) = self.data.split_at(L);
+        self.data = rest;
+        head.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take::<1>().map(|bytes| bytes[0])
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.take().map(u64::from_le_bytes)
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.take().map(i64::from_le_bytes)
+    }
+
+    fn u128(&mut self) -> Option<u128> {
+        self.take().map(u128::from_le_bytes)
+    }
+
+    fn pubkey(&mut self) -> Option<[u8; 32]> {
+        self.take()
+    }
+
+    /// Checks and skips the account discriminator
+    fn discriminator(&mut self, expected: [u8; 8]) -> Option<()> {
+        (self.take::<8>()? == expected).then_some(())
+    }
+}
+
+impl<const N: usize> PoolStateAccount<N> {
+    /// Reads a pool from raw account data, discriminator included. Returns None for anything
+    /// that isn't a pool state in the current layout.
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data };
+        reader.discriminator(POOL_STATE_DISCRIMINATOR)?;
+
+        let authority = reader.pubkey()?;
+        let bump = reader.u8()?;
+        let amount = reader.u64()?;
+        let token_vault = reader.pubkey()?;
+        let token_mint = reader.pubkey()?;
+        let initialized_at = reader.i64()?;
+        let vault_bump = reader.u8()?;
+        let vault_auth_bump = reader.u8()?;
+        let vault_authority = reader.pubkey()?;
+        let distribution_rate = reader.u128()?;
+        let user_deposit_amt = reader.u64()?;
+        let pending_authority = reader.pubkey()?;
+        let emission_rate = reader.u64()?;
+        let emission_start = reader.i64()?;
+        let emission_end = reader.i64()?;
+        let last_emission_update = reader.i64()?;
+        let mut reward_infos = [RewardInfoAccount::default(); N];
+        for reward_info in reward_infos.iter_mut() {
+            *reward_info = RewardInfoAccount {
+                mint: reader.pubkey()?,
+                vault: reader.pubkey()?,
+                vault_bump: reader.u8()?,
+                reward_rate: reader.u128()?,
+                prev_scale_reward_rate: reader.u128()?,
+                epoch_end_reward_rate: reader.u128()?,
+            };
+        }
+
+        let mut pool = PoolStateAccount {
+            authority,
+            bump,
+            amount,
+            token_vault,
+            token_mint,
+            initialized_at,
+            vault_bump,
+            vault_auth_bump,
+            vault_authority,
+            distribution_rate,
+            user_deposit_amt,
+            pending_authority,
+            emission_rate,
+            emission_start,
+            emission_end,
+            last_emission_update,
+            reward_infos,
+            boost_rate: reader.u128()?,
+            total_boost_weight: reader.u128()?,
+            boost_reserve: reader.u64()?,
+            unbonding_period: reader.i64()?,
+            pending_withdrawal: reader.u64()?,
+            rate_scale: reader.u64()?,
+            prev_scale_boost_rate: reader.u128()?,
+            epoch: reader.u64()?,
+            epoch_end_rate_scale: reader.u64()?,
+            epoch_end_boost_rate: reader.u128()?,
+            deactivated: reader.bool()?,
+            paused: reader.u8()?,
+            distributor: reader.pubkey()?,
+            burner: reader.pubkey()?,
+            guardian: reader.pubkey()?,
+            rewards_owed_total: [0; N],
+            generation: 0,
+        };
+        for owed in pool.rewards_owed_total.iter_mut() {
+            *owed = reader.u64()?;
+        }
+        pool.generation = reader.u64()?;
+
+        Some(pool)
+    }
+
+    /// Streamed rewards not yet distributed at `now`, they only stream inside the emission window,
+    /// since the last update and while distributions aren't paused
+    pub fn pending_emissions(&self, now: i64) -> MathResult<u64> {
+        let start = self.emission_start.max(self.last_emission_update);
+        let end = self.emission_end.min(now);
+
+        if end <= start || self.emission_rate == 0 || self.paused & PAUSE_DISTRIBUTE != 0 {
+            return Ok(0);
+        }
+
+        ((end - start) as u64).checked_mul(self.emission_rate).ok_or(MathError::Overflow)
+    }
+
+    /// The engine as `view_position` and `unstake` see it at `now`, with pending emissions distributed
+    pub fn engine_at(&self, now: i64) -> MathResult<Pool<N>> {
+        let mut engine = self.engine();
+        let emissions = self.pending_emissions(now)?;
+        if emissions != 0 && engine.amount != 0 {
+            engine.distribute(emissions)?;
+        }
+
+        Ok(engine)
+    }
+
+    /// The pool's accounting state as the engine sees it
+    pub fn engine(&self) -> Pool<N> {
+        let mut rewards = [Accumulator::default(); N];
+        for (accumulator, reward_info) in rewards.iter_mut().zip(self.reward_infos.iter()) {
+            *accumulator = Accumulator {
+                current: reward_info.reward_rate,
+                prev_scale_total: reward_info.prev_scale_reward_rate,
+                epoch_end_total: reward_info.epoch_end_reward_rate,
+            };
+        }
+
+        Pool {
+            amount: self.amount,
+            user_deposit_amt: self.user_deposit_amt,
+            distribution_rate: self.distribution_rate,
+            rate_scale: self.rate_scale,
+            epoch: self.epoch,
+            epoch_end_rate_scale: self.epoch_end_rate_scale,
+            total_boost_weight: self.total_boost_weight,
+            boost_reserve: self.boost_reserve,
+            boost: Accumulator {
+                current: self.boost_rate,
+                prev_scale_total: self.prev_scale_boost_rate,
+                epoch_end_total: self.epoch_end_boost_rate,
+            },
+            rewards,
+        }
+    }
+
+    /// Writes an engine result back into the account fields it came from
+    pub fn save_engine(&mut self, engine: &Pool<N>) {
+        self.amount = engine.amount;
+        self.user_deposit_amt = engine.user_deposit_amt;
+        self.distribution_rate = engine.distribution_rate;
+        self.rate_scale = engine.rate_scale;
+        self.epoch = engine.epoch;
+        self.epoch_end_rate_scale = engine.epoch_end_rate_scale;
+        self.total_boost_weight = engine.total_boost_weight;
+        self.boost_reserve = engine.boost_reserve;
+        self.boost_rate = engine.boost.current;
+        self.prev_scale_boost_rate = engine.boost.prev_scale_total;
+        self.epoch_end_boost_rate = engine.boost.epoch_end_total;
+        for (reward_info, accumulator) in self.reward_infos.iter_mut().zip(engine.rewards.iter()) {
+            reward_info.reward_rate = accumulator.current;
+            reward_info.prev_scale_reward_rate = accumulator.prev_scale_total;
+            reward_info.epoch_end_reward_rate = accumulator.epoch_end_total;
+        }
+    }
+}
+
+impl<const N: usize> StakeEntryAccount<N> {
+    /// Reads a stake entry from raw account data, discriminator included. Returns None for
+    /// anything that isn't a stake entry in the current layout.
+    pub fn unpack(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data };
+        reader.discriminator(STAKE_ENTRY_DISCRIMINATOR)?;
+
+        let user = reader.pubkey()?;
+        let bump = reader.u8()?;
+        let balance = reader.u64()?;
+        let last_staked = reader.i64()?;
+        let initial_distribution_rate = reader.u128()?;
+        let mut reward_checkpoints = [0; N];
+        for checkpoint in reward_checkpoints.iter_mut() {
+            *checkpoint = reader.u128()?;
+        }
+        let mut rewards_owed = [0; N];
+        for owed in rewards_owed.iter_mut() {
+            *owed = reader.u64()?;
+        }
+
+        Some(StakeEntryAccount {
+            user,
+            bump,
+            balance,
+            last_staked,
+            initial_distribution_rate,
+            reward_checkpoints,
+            rewards_owed,
+            lock_end: reader.i64()?,
+            lock_weight_bps: reader.u64()?,
+            boost_weight: reader.u64()?,
+            boost_checkpoint: reader.u128()?,
+            unbonding_amount: reader.u64()?,
+            unbonding_end: reader.i64()?,
+            initial_rate_scale: reader.u64()?,
+            initial_epoch: reader.u64()?,
+            position_mint: reader.pubkey()?,
+            pool_generation: reader.u64()?,
+        })
+    }
+
+    /// The entry's accounting state as the engine sees it
+    pub fn position(&self) -> Position<N> {
+        Position {
+            balance: self.balance,
+            initial_distribution_rate: self.initial_distribution_rate,
+            initial_rate_scale: self.initial_rate_scale,
+            initial_epoch: self.initial_epoch,
+            lock_end: self.lock_end,
+            lock_weight_bps: self.lock_weight_bps,
+            boost_weight: self.boost_weight,
+            boost_checkpoint: self.boost_checkpoint,
+            reward_checkpoints: self.reward_checkpoints,
+            rewards_owed: self.rewards_owed,
+        }
+    }
+
+    /// Writes an engine result back into the account fields it came from
+    pub fn save_position(&mut self, position: &Position<N>) {
+        self.balance = position.balance;
+        self.initial_distribution_rate = position.initial_distribution_rate;
+        self.initial_rate_scale = position.initial_rate_scale;
+        self.initial_epoch = position.initial_epoch;
+        self.lock_end = position.lock_end;
+        self.lock_weight_bps = position.lock_weight_bps;
+        self.boost_weight = position.boost_weight;
+        self.boost_checkpoint = position.boost_checkpoint;
+        self.reward_checkpoints = position.reward_checkpoints;
+        self.rewards_owed = position.rewards_owed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RATE_PRECISION;
+
+    const MULT: u64 = 1_000_000;
+
+    // a stake entry with no reward slots, in the order the program writes it
+    fn entry_data(balance: u64, initial_distribution_rate: u128) -> Vec<u8> {
+        let mut data = STAKE_ENTRY_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[1; 32]);
+        data.push(255);
+        data.extend_from_slice(&balance.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&initial_distribution_rate.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&10_000u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u128.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data
+    }
+
+    // a pool streaming MULT per second from 0 to 100, last updated at 0
+    fn emitting_pool(amount: u64) -> PoolStateAccount<0> {
+        let mut pool = PoolStateAccount {
+            authority: [0; 32],
+            bump: 0,
+            amount: 0,
+            token_vault: [0; 32],
+            token_mint: [0; 32],
+            initialized_at: 0,
+            vault_bump: 0,
+            vault_auth_bump: 0,
+            vault_authority: [0; 32],
+            distribution_rate: 0,
+            user_deposit_amt: 0,
+            pending_authority: [0; 32],
+            emission_rate: MULT,
+            emission_start: 0,
+            emission_end: 100,
+            last_emission_update: 0,
+            reward_infos: [],
+            boost_rate: 0,
+            total_boost_weight: 0,
+            boost_reserve: 0,
+            unbonding_period: 0,
+            pending_withdrawal: 0,
+            rate_scale: 0,
+            prev_scale_boost_rate: 0,
+            epoch: 0,
+            epoch_end_rate_scale: 0,
+            epoch_end_boost_rate: 0,
+            deactivated: false,
+            paused: 0,
+            distributor: [0; 32],
+            burner: [0; 32],
+            guardian: [0; 32],
+            rewards_owed_total: [],
+            generation: 0,
+        };
+        let mut engine = Pool::<0>::new();
+        engine.amount = amount;
+        engine.user_deposit_amt = amount;
+        pool.save_engine(&engine);
+        pool
+    }
+
+    #[test]
+    fn unpacked_entry_quotes_like_the_engine() {
+        let data = entry_data(100 * MULT, RATE_PRECISION);
+        let entry = StakeEntryAccount::<0>::unpack(&data).unwrap();
+        assert_eq!(entry.user, [1; 32]);
+        assert_eq!(entry.bump, 255);
+
+        let mut pool = Pool::<0>::new();
+        pool.amount = 100 * MULT;
+        pool.user_deposit_amt = 100 * MULT;
+        pool.distribute(10 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&entry.position()).unwrap(), 110 * MULT);
+    }
+
+    #[test]
+    fn unpack_refuses_other_accounts() {
+        let data = entry_data(100 * MULT, RATE_PRECISION);
+        assert_eq!(StakeEntryAccount::<0>::unpack(&data[..data.len() - 1]), None);
+        // entries with reward slots are longer than this one
+        assert_eq!(StakeEntryAccount::<1>::unpack(&data), None);
+
+        let mut pool_data = data.clone();
+        pool_data[..8].copy_from_slice(&POOL_STATE_DISCRIMINATOR);
+        assert_eq!(StakeEntryAccount::<0>::unpack(&pool_data), None);
+        assert_eq!(PoolStateAccount::<0>::unpack(&data), None);
+    }
+
+    #[test]
+    fn quotes_skip_paused_emissions() {
+        let entry = StakeEntryAccount::<0>::unpack(&entry_data(100 * MULT, RATE_PRECISION)).unwrap();
+        let mut pool = emitting_pool(100 * MULT);
+        assert_eq!(pool.pending_emissions(10).unwrap(), 10 * MULT);
+        assert_eq!(pool.engine_at(10).unwrap().settled_balance(&entry.position()).unwrap(), 110 * MULT);
+
+        pool.paused = PAUSE_DISTRIBUTE;
+        assert_eq!(pool.pending_emissions(10).unwrap(), 0);
+        assert_eq!(pool.engine_at(10).unwrap().settled_balance(&entry.position()).unwrap(), 100 * MULT);
+
+        // other pause bits don't stop streaming
+        pool.paused = PAUSE_ALL & !PAUSE_DISTRIBUTE;
+        assert_eq!(pool.pending_emissions(10).unwrap(), 10 * MULT);
+    }
+}
\ No newline at end of file
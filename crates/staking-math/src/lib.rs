@@ -0,0 +1,33 @@
+//! Accounting engine for the RND staking pool.
+//!
+//! Plain structs and integer math only, no Solana dependencies and no logging, so the on-chain
+//! program and off-chain clients run exactly the same code. A quote computed with
+//! [`Pool::settled_balance`] is what `unstake` will pay for the same pool and position state, and
+//! [`PoolStateAccount`] and [`StakeEntryAccount`] build that state from fetched account data.
+#![cfg_attr(not(test), no_std)]
+
+pub mod accounts;
+pub mod math;
+pub mod pool;
+
+pub use accounts::*;
+pub use math::{RATE_PRECISION, SCALE_FACTOR};
+pub use pool::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    /// A result doesn't fit in its type
+    Overflow,
+    /// A subtraction went below zero
+    Underflow,
+    /// Nothing is staked to share a distribution between
+    ZeroStakedSupply,
+    /// Attempted to burn more than is staked
+    BurnExceedsPool,
+    /// A position was checkpointed at a rate of zero
+    InvalidDistributionRate,
+    /// Attempted to take more than a position's settled balance
+    Overdraw,
+}
+
+pub type MathResult<T> = core::result::Result<T, MathError>;
\ No newline at end of file
@@ -0,0 +1,199 @@
+// construct_uint expands to code clippy flags
+#![allow(clippy::assign_op_pattern)]
+#![allow(unknown_lints, clippy::manual_div_ceil)]
+
+use uint::construct_uint;
+
+construct_uint! {
+    /// 192 bit integer for intermediate results, wide enough for the product of two u128 values of the sizes used here
+    pub struct U192(3);
+}
+
+/// Fixed point scale of the distribution rate and the reward accumulators, 1.0 == RATE_PRECISION
+pub const RATE_PRECISION: u128 = 1_000_000_000_000_000_000;
+/// The distribution rate is multiplied by this and the rate scale bumped when burns push it below it
+pub const SCALE_FACTOR: u128 = 1_000_000_000;
+
+/// Calculates floor(a * b / c) without overflowing the intermediate product
+pub fn mul_div(a: u128, b: u128, c: u128) -> Option<u128> {
+    let result = U192::from(a).checked_mul(U192::from(b))?.checked_div(U192::from(c))?;
+    u128::try_from(result).ok()
+}
+
+/// Multiplies a distribution rate by numerator / denominator. If the result drops below
+/// SCALE_FACTOR it is multiplied by SCALE_FACTOR until it doesn't, returning the new rate
+/// and how many times it was scaled. A numerator of 0 returns a rate of 0.
+pub fn scale_rate(rate: u128, numerator: u128, denominator: u128) -> Option<(u128, u64)> {
+    let mut product = U192::from(rate).checked_mul(U192::from(numerator))?;
+    let denominator = U192::from(denominator);
+    let mut scale_changes = 0;
+
+    while !product.is_zero() && product.checked_div(denominator)? < U192::from(SCALE_FACTOR) {
+        product = product.checked_mul(U192::from(SCALE_FACTOR))?;
+        scale_changes += 1;
+    }
+
+    let new_rate = u128::try_from(product.checked_div(denominator)?).ok()?;
+    Some((new_rate, scale_changes))
+}
+
+/// Settles a balance staked at initial_rate against the current rate, scale_diff scale
+/// changes later. After two or more the rate has fallen by at least SCALE_FACTOR, so the
+/// balance rounds to zero.
+pub fn settle_balance(balance: u64, rate: u128, initial_rate: u128, scale_diff: u64) -> Option<u64> {
+    let settled = match scale_diff {
+        0 => mul_div(balance as u128, rate, initial_rate)?,
+        1 => mul_div(balance as u128, rate, initial_rate.checked_mul(SCALE_FACTOR)?)?,
+        _ => 0
+    };
+    u64::try_from(settled).ok()
+}
+
+/// Earnings of `weight` on a per-unit accumulator since `checkpoint`, divided by `denominator`.
+/// Accumulators restart from zero on a scale change, `prev_scale_total` is where the previous
+/// scale's accumulator ended. Anything earned more than one scale change back is not recoverable
+/// and is dropped.
+pub fn accumulated_earnings(
+    weight: u128,
+    current: u128,
+    prev_scale_total: u128,
+    checkpoint: u128,
+    scale_diff: u64,
+    denominator: u128,
+) -> Option<u64> {
+    let earnings = match scale_diff {
+        0 => mul_div(weight, current.checked_sub(checkpoint)?, denominator)?,
+        1 => mul_div(weight, prev_scale_total.checked_sub(checkpoint)?, denominator)?
+            .checked_add(mul_div(weight, current, denominator.checked_mul(SCALE_FACTOR)?)?)?,
+        _ => 0
+    };
+    u64::try_from(earnings).ok()
+}
+
+/// Divides a value by SCALE_FACTOR once per scale change
+pub fn descale(value: u128, scale_diff: u64) -> u128 {
+    match scale_diff {
+        0 => value,
+        1 => value / SCALE_FACTOR,
+        _ => 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // settling only ever rounds down, by less than one unit of the rate per step plus the final truncation
+    fn assert_close(settled: f64, exact: f64, steps: u32) -> Result<(), TestCaseError> {
+        prop_assert!(settled <= exact * (1.0 + 1e-12) + 1.0, "settled {} above exact {}", settled, exact);
+        prop_assert!(settled >= exact * (1.0 - steps as f64 / SCALE_FACTOR as f64) - 1.0, "settled {} too far below exact {}", settled, exact);
+        Ok(())
+    }
+
+    #[test]
+    fn mul_div_handles_wide_products() {
+        assert_eq!(mul_div(u128::MAX, RATE_PRECISION, RATE_PRECISION), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn scale_rate_rescales_below_scale_factor() {
+        // 1.0 -> 1e-12 drops below SCALE_FACTOR and is rescaled once
+        let (rate, scale_changes) = scale_rate(RATE_PRECISION, 1, 1_000_000_000_000).unwrap();
+        assert_eq!(scale_changes, 1);
+        assert_eq!(rate, 1_000_000 * SCALE_FACTOR);
+
+        // a full burn leaves a rate of 0
+        assert_eq!(scale_rate(RATE_PRECISION, 0, 100), Some((0, 0)));
+    }
+
+    proptest! {
+        // a sequence of distributions and burns applied to the rate should settle a position to
+        // within a tiny relative error of applying the same changes to the balance exactly
+        #[test]
+        fn settled_balance_tracks_exact_growth(
+            balance in 1_000u64..1_000_000_000_000,
+            changes in prop::collection::vec((1_000_000u64..1_000_000_000_000, 1u64..2_000, any::<bool>()), 1..64)
+        ) {
+            let mut rate = RATE_PRECISION;
+            let mut exact = balance as f64;
+
+            // each change moves the pool by up to 20%, so the rate stays well above SCALE_FACTOR
+            for (pool_amount, change_bps, is_burn) in changes {
+                let change = pool_amount / 10_000 * change_bps;
+                let numerator = if is_burn { pool_amount - change } else { pool_amount + change };
+                let (new_rate, scale_changes) = scale_rate(rate, numerator as u128, pool_amount as u128).unwrap();
+                prop_assert_eq!(scale_changes, 0);
+                rate = new_rate;
+                exact = exact * numerator as f64 / pool_amount as f64;
+            }
+
+            let settled = settle_balance(balance, rate, RATE_PRECISION, 0).unwrap() as f64;
+            assert_close(settled, exact, 64)?;
+        }
+
+        // the same holds for a position that lives through a scale change
+        #[test]
+        fn settled_balance_tracks_exact_growth_across_scale_change(
+            balance in 1_000_000_000_000_000u64..10_000_000_000_000_000_000,
+            wipe in 101u64..1_000,
+            changes in prop::collection::vec((1_000_000u64..1_000_000_000_000, 1u64..2_000, any::<bool>()), 1..64)
+        ) {
+            // a burn leaving one part in wipe * 1e7 pushes the rate below SCALE_FACTOR
+            let survivors = wipe as u128 * 10_000_000;
+            let (mut rate, mut scale) = scale_rate(RATE_PRECISION, 1, survivors).unwrap();
+            prop_assert_eq!(scale, 1);
+            let mut exact = balance as f64 / survivors as f64;
+
+            for (pool_amount, change_bps, is_burn) in changes {
+                let change = pool_amount / 10_000 * change_bps;
+                let numerator = if is_burn { pool_amount - change } else { pool_amount + change };
+                let (new_rate, scale_changes) = scale_rate(rate, numerator as u128, pool_amount as u128).unwrap();
+                rate = new_rate;
+                scale += scale_changes;
+                exact = exact * numerator as f64 / pool_amount as f64;
+            }
+            prop_assume!(scale == 1);
+
+            let settled = settle_balance(balance, rate, RATE_PRECISION, scale).unwrap() as f64;
+            assert_close(settled, exact, 65)?;
+        }
+
+        // settling never pays out more than was distributed, whatever the order of stakes
+        #[test]
+        fn settled_balances_never_exceed_pool(
+            balances in prop::collection::vec(1u64..1_000_000_000_000, 1..20),
+            distributions in prop::collection::vec(1u64..1_000_000_000_000, 1..50)
+        ) {
+            let mut pool_amount: u128 = balances.iter().map(|b| *b as u128).sum();
+            let mut rate = RATE_PRECISION;
+
+            for amount in distributions {
+                let (new_rate, scale_changes) = scale_rate(rate, pool_amount + amount as u128, pool_amount).unwrap();
+                prop_assert_eq!(scale_changes, 0);
+                rate = new_rate;
+                pool_amount += amount as u128;
+            }
+
+            let settled: u128 = balances.iter()
+                .map(|b| settle_balance(*b, rate, RATE_PRECISION, 0).unwrap() as u128)
+                .sum();
+            prop_assert!(settled <= pool_amount);
+        }
+
+        #[test]
+        fn accumulated_earnings_spans_one_scale_change(
+            weight in 1u128..1_000_000_000_000_000_000,
+            before in 0u128..RATE_PRECISION,
+            after in 0u128..RATE_PRECISION
+        ) {
+            // earning `before` in the old scale then `after` in the new one is the same as
+            // earning before + after / SCALE_FACTOR without a scale change
+            let split = accumulated_earnings(weight, after, before, 0, 1, RATE_PRECISION).unwrap() as u128;
+            let single = mul_div(weight, before * SCALE_FACTOR + after, RATE_PRECISION * SCALE_FACTOR).unwrap();
+            prop_assert!(split <= single && single - split <= 1);
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,546 @@
+use crate::{math::*, MathError, MathResult};
+
+pub const MAX_LOCK_DURATION: i64 = 365 * 24 * 60 * 60;
+pub const BASE_WEIGHT_BPS: u64 = 10_000;
+pub const MAX_WEIGHT_BPS: u64 = 20_000;
+/// Unstake amount that takes a position's whole settled balance, whatever it has grown to by then
+pub const UNSTAKE_ALL: u64 = u64::MAX;
+
+/// A per-unit accumulator, restarted on every scale change and every epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Accumulator {
+    pub current: u128,
+    /// Where the accumulator ended in the previous scale of the current epoch
+    pub prev_scale_total: u128,
+    /// Where the accumulator ended in the previous epoch
+    pub epoch_end_total: u128,
+}
+
+/// Accounting state of a pool, N is the number of reward mint slots
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool<const N: usize> {
+    pub amount: u64,
+    pub user_deposit_amt: u64,
+    pub distribution_rate: u128,
+    pub rate_scale: u64,
+    pub epoch: u64,
+    pub epoch_end_rate_scale: u64,
+    pub total_boost_weight: u128,
+    pub boost_reserve: u64,
+    pub boost: Accumulator,
+    pub rewards: [Accumulator; N],
+}
+
+/// Accounting state of a single stake position in a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position<const N: usize> {
+    pub balance: u64,
+    pub initial_distribution_rate: u128,
+    pub initial_rate_scale: u64,
+    pub initial_epoch: u64,
+    pub lock_end: i64,
+    pub lock_weight_bps: u64,
+    pub boost_weight: u64,
+    pub boost_checkpoint: u128,
+    pub reward_checkpoints: [u128; N],
+    pub rewards_owed: [u64; N],
+}
+
+impl<const N: usize> Default for Pool<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Pool<N> {
+    /// An empty pool with a distribution rate of 1.0
+    pub fn new() -> Self {
+        Pool {
+            amount: 0,
+            user_deposit_amt: 0,
+            distribution_rate: RATE_PRECISION,
+            rate_scale: 0,
+            epoch: 0,
+            epoch_end_rate_scale: 0,
+            total_boost_weight: 0,
+            boost_reserve: 0,
+            boost: Accumulator::default(),
+            rewards: [Accumulator::default(); N],
+        }
+    }
+
+    /// An empty position checkpointed at the pool's current state
+    pub fn open_position(&self) -> Position<N> {
+        let mut position = Position {
+            balance: 0,
+            initial_distribution_rate: 0,
+            initial_rate_scale: 0,
+            initial_epoch: 0,
+            lock_end: 0,
+            lock_weight_bps: BASE_WEIGHT_BPS,
+            boost_weight: 0,
+            boost_checkpoint: self.boost.current,
+            reward_checkpoints: [0; N],
+            rewards_owed: [0; N],
+        };
+        for (checkpoint, reward) in position.reward_checkpoints.iter_mut().zip(self.rewards.iter()) {
+            *checkpoint = reward.current;
+        }
+        self.checkpoint(&mut position);
+        position
+    }
+
+    /// Shares `amount` new tokens among stakers by stake weight
+    pub fn distribute(&mut self, amount: u64) -> MathResult<()> {
+        // locked positions have extra weight, their share of the distribution goes to the boost reserve
+        let amount = self.distribute_boost(amount)?;
+
+        if self.amount != 0 {
+            // every staked token grows by (total + rewards) / total
+            let new_amount = (self.amount as u128).checked_add(amount as u128).ok_or(MathError::Overflow)?;
+            self.change_rate(new_amount, self.amount as u128)?;
+        }
+
+        self.amount = self.amount.checked_add(amount).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+
+    /// Takes `amount` tokens away from stakers in proportion to their staked balance
+    pub fn burn(&mut self, amount: u64) -> MathResult<()> {
+        // only staked tokens can be burned, never the boost reserve
+        let new_amount = self.amount.checked_sub(amount).ok_or(MathError::BurnExceedsPool)?;
+
+        if self.amount != 0 {
+            // every staked token shrinks by (total - burned) / total
+            self.change_rate(new_amount as u128, self.amount as u128)?;
+        }
+
+        self.amount = new_amount;
+        Ok(())
+    }
+
+    /// Shares `amount` tokens of the reward mint in slot `index` among stakers
+    pub fn distribute_reward(&mut self, index: usize, amount: u64) -> MathResult<()> {
+        // reward per staked token, scaled by the current distribution rate so that
+        // positions which grew or shrank since their checkpoint earn in proportion
+        let increase = mul_div(amount as u128, self.distribution_rate, self.amount as u128).ok_or(MathError::ZeroStakedSupply)?;
+
+        let reward = &mut self.rewards[index];
+        reward.current = reward.current.checked_add(increase).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+
+    /// Adds `amount` to a position, extending its lock to at least `lock_duration` from `now`
+    pub fn stake(&mut self, position: &mut Position<N>, amount: u64, lock_duration: i64, now: i64) -> MathResult<()> {
+        if position.balance == 0 {
+            self.settle_rewards(position)?;
+            self.checkpoint(position);
+        } else {
+            // create new staking position with rewards/burn amount included
+            self.compound(position, now)?;
+        }
+
+        self.amount = self.amount.checked_add(amount).ok_or(MathError::Overflow)?;
+        self.user_deposit_amt = self.user_deposit_amt.checked_add(amount).ok_or(MathError::Overflow)?;
+
+        // a lock can be extended but never shortened and the weight follows the time left on it
+        self.remove_boost_weight(position)?;
+        position.lock_end = position.lock_end.max(now.checked_add(lock_duration).ok_or(MathError::Overflow)?);
+        position.balance = position.balance.checked_add(amount).ok_or(MathError::Overflow)?;
+        self.add_boost_weight(position, now)
+    }
+
+    /// The amount an unstake of `amount` takes, the whole settled balance for UNSTAKE_ALL
+    pub fn unstake_amount(&self, position: &Position<N>, amount: u64) -> MathResult<u64> {
+        if amount == UNSTAKE_ALL {
+            return self.settled_balance(position)
+        }
+
+        Ok(amount)
+    }
+
+    /// Takes `amount` out of a position's settled balance, the rest stays staked. Returns what is left staked.
+    pub fn unstake(&mut self, position: &mut Position<N>, amount: u64, now: i64) -> MathResult<u64> {
+        let out_amount = self.settled_balance(position)?;
+        let remaining_amount = out_amount.checked_sub(amount).ok_or(MathError::Overdraw)?;
+
+        // lock in reward mint and boost earnings before the position changes
+        self.settle_rewards(position)?;
+        self.release_boost(position)?;
+        self.remove_boost_weight(position)?;
+
+        // replace the amount the user had staked with what is left staked after settling rewards/burns
+        self.amount = self.amount.checked_sub(amount).ok_or(MathError::Underflow)?;
+        self.user_deposit_amt = self.user_deposit_amt.checked_sub(position.balance).ok_or(MathError::Underflow)?
+            .checked_add(remaining_amount).ok_or(MathError::Overflow)?;
+
+        position.balance = remaining_amount;
+        self.checkpoint(position);
+        self.add_boost_weight(position, now)?;
+        Ok(remaining_amount)
+    }
+
+    /// Compounds a position whose lock weight is above what the time left on its lock is worth, so the
+    /// weight comes down to it. Returns false and leaves a position with a current weight alone.
+    pub fn poke(&mut self, position: &mut Position<N>, now: i64) -> MathResult<bool> {
+        if position.lock_weight_bps <= lock_weight_bps_at(position.lock_end, now)? {
+            return Ok(false)
+        }
+
+        self.compound(position, now)?;
+        Ok(true)
+    }
+
+    /// Takes a position's growth above its principal, the principal stays staked. Returns the amount claimed.
+    pub fn claim(&mut self, position: &mut Position<N>, now: i64) -> MathResult<u64> {
+        // burns have taken the position below principal, the checkpoint is kept so the loss
+        // is still realized when the user unstakes
+        let out_amount = self.settled_balance(position)?;
+        if out_amount <= position.balance {
+            return Ok(0)
+        }
+        let claim_amount = out_amount - position.balance;
+
+        // lock in reward mint and boost earnings before the checkpoint moves
+        self.settle_rewards(position)?;
+        self.release_boost(position)?;
+        self.remove_boost_weight(position)?;
+
+        // user_deposit_amt is unchanged since the balance is
+        self.amount = self.amount.checked_sub(claim_amount).ok_or(MathError::Underflow)?;
+        self.checkpoint(position);
+        self.add_boost_weight(position, now)?;
+        Ok(claim_amount)
+    }
+
+    /// Rolls the rewards and burns a position has settled into its staked balance
+    pub fn compound(&mut self, position: &mut Position<N>, now: i64) -> MathResult<()> {
+        self.settle_rewards(position)?;
+
+        let out_amount = self.settled_balance(position)?;
+        self.release_boost(position)?;
+        self.remove_boost_weight(position)?;
+
+        // deposits track the new balance
+        self.user_deposit_amt = self.user_deposit_amt.checked_sub(position.balance).ok_or(MathError::Underflow)?
+            .checked_add(out_amount).ok_or(MathError::Overflow)?;
+        position.balance = out_amount;
+
+        // the weight drops to the time left on the lock here, base weight once it has ended
+        self.checkpoint(position);
+        self.add_boost_weight(position, now)
+    }
+
+    /// Balance a position would be paid if it unstaked everything now
+    pub fn settled_balance(&self, position: &Position<N>) -> MathResult<u64> {
+        // positions from before a wipe were burned along with the rest of the pool
+        let staked_amount = if position.initial_epoch == self.epoch {
+            settle_balance(
+                position.balance,
+                self.distribution_rate,
+                position.initial_distribution_rate,
+                self.scale_diff(position)?
+            ).ok_or(MathError::InvalidDistributionRate)?
+        } else {
+            0
+        };
+
+        staked_amount.checked_add(self.boost_earnings(position)?).ok_or(MathError::Overflow)
+    }
+
+    /// Extra rewards a locked position has earned since its last checkpoint
+    pub fn boost_earnings(&self, position: &Position<N>) -> MathResult<u64> {
+        let weight = mul_div(position.boost_weight as u128, RATE_PRECISION, position.initial_distribution_rate)
+            .ok_or(MathError::InvalidDistributionRate)?;
+
+        self.earnings(position, weight, &self.boost, position.boost_checkpoint, RATE_PRECISION)
+    }
+
+    /// Adds reward mint earnings since the last checkpoint to what the position is owed
+    pub fn settle_rewards(&self, position: &mut Position<N>) -> MathResult<()> {
+        // must run before balance or initial_distribution_rate change, earnings are
+        // measured against the position as it was at the last checkpoint
+        for (index, reward) in self.rewards.iter().enumerate() {
+            let earned = self.earnings(
+                position,
+                position.balance as u128,
+                reward,
+                position.reward_checkpoints[index],
+                position.initial_distribution_rate
+            )?;
+
+            position.rewards_owed[index] = position.rewards_owed[index].checked_add(earned).ok_or(MathError::Overflow)?;
+            position.reward_checkpoints[index] = reward.current;
+        }
+
+        Ok(())
+    }
+
+    /// The position is settled from here on, at the pool's current rate, scale and epoch
+    pub fn checkpoint(&self, position: &mut Position<N>) {
+        position.initial_distribution_rate = self.distribution_rate;
+        position.initial_rate_scale = self.rate_scale;
+        position.initial_epoch = self.epoch;
+    }
+
+    fn change_rate(&mut self, numerator: u128, denominator: u128) -> MathResult<()> {
+        let (distribution_rate, scale_changes) = scale_rate(self.distribution_rate, numerator, denominator)
+            .ok_or(MathError::Overflow)?;
+        self.distribution_rate = distribution_rate;
+
+        // everything staked was burned, there is nothing left to scale
+        if distribution_rate == 0 {
+            self.start_new_epoch();
+            return Ok(())
+        }
+
+        if scale_changes == 0 {
+            return Ok(())
+        }
+
+        // the rate was multiplied by SCALE_FACTOR to keep its precision. Boost weights are stored against
+        // the old scale so they shrink by the same factor, and the accumulators restart for the new scale
+        self.rate_scale = self.rate_scale.checked_add(scale_changes).ok_or(MathError::Overflow)?;
+        self.total_boost_weight = descale(self.total_boost_weight, scale_changes);
+        for accumulator in core::iter::once(&mut self.boost).chain(self.rewards.iter_mut()) {
+            accumulator.prev_scale_total = if scale_changes == 1 { accumulator.current } else { 0 };
+            accumulator.current = 0;
+        }
+
+        Ok(())
+    }
+
+    fn start_new_epoch(&mut self) {
+        // keep where the accumulators ended so positions from this epoch can still collect what they earned
+        self.epoch_end_rate_scale = self.rate_scale;
+        for accumulator in core::iter::once(&mut self.boost).chain(self.rewards.iter_mut()) {
+            accumulator.epoch_end_total = accumulator.current;
+            accumulator.current = 0;
+            accumulator.prev_scale_total = 0;
+        }
+
+        // positions from older epochs settle to zero, new stakers start from a rate of 1.0 again
+        self.epoch = self.epoch.saturating_add(1);
+        self.distribution_rate = RATE_PRECISION;
+        self.rate_scale = 0;
+        self.total_boost_weight = 0;
+    }
+
+    fn scale_diff(&self, position: &Position<N>) -> MathResult<u64> {
+        self.rate_scale.checked_sub(position.initial_rate_scale).ok_or(MathError::Underflow)
+    }
+
+    fn earnings(&self, position: &Position<N>, weight: u128, accumulator: &Accumulator, checkpoint: u128, denominator: u128) -> MathResult<u64> {
+        let earnings = if position.initial_epoch == self.epoch {
+            accumulated_earnings(weight, accumulator.current, accumulator.prev_scale_total, checkpoint, self.scale_diff(position)?, denominator)
+        } else if position.initial_epoch.checked_add(1) == Some(self.epoch)
+            && position.initial_rate_scale == self.epoch_end_rate_scale {
+            // the position earned up to the wipe that ended its epoch, nothing after
+            accumulated_earnings(weight, accumulator.epoch_end_total, 0, checkpoint, 0, denominator)
+        } else {
+            Some(0)
+        };
+
+        earnings.ok_or(MathError::Overflow)
+    }
+
+    fn position_boost_weight(&self, position: &Position<N>) -> MathResult<u128> {
+        // the total boost weight restarts with each epoch
+        if position.initial_epoch != self.epoch {
+            return Ok(0)
+        }
+
+        // boost weight is stored relative to a distribution rate of 1.0 in the position's scale
+        let weight = mul_div(position.boost_weight as u128, RATE_PRECISION, position.initial_distribution_rate)
+            .ok_or(MathError::InvalidDistributionRate)?;
+
+        Ok(descale(weight, self.scale_diff(position)?))
+    }
+
+    fn distribute_boost(&mut self, amount: u64) -> MathResult<u64> {
+        if self.total_boost_weight == 0 || self.amount == 0 {
+            return Ok(amount)
+        }
+
+        // bring the total boost weight to today's terms
+        let boost_weight = mul_div(self.total_boost_weight, self.distribution_rate, RATE_PRECISION).ok_or(MathError::Overflow)?;
+        let total_weight = (self.amount as u128).checked_add(boost_weight).ok_or(MathError::Overflow)?;
+
+        // staked tokens get their base share through the distribution rate
+        let base_amount = mul_div(amount as u128, self.amount as u128, total_weight).ok_or(MathError::ZeroStakedSupply)? as u64;
+        let boost_amount = amount.checked_sub(base_amount).ok_or(MathError::Underflow)?;
+
+        let increase = mul_div(boost_amount as u128, RATE_PRECISION, self.total_boost_weight).ok_or(MathError::Overflow)?;
+        self.boost.current = self.boost.current.checked_add(increase).ok_or(MathError::Overflow)?;
+        self.boost_reserve = self.boost_reserve.checked_add(boost_amount).ok_or(MathError::Overflow)?;
+
+        Ok(base_amount)
+    }
+
+    fn release_boost(&mut self, position: &Position<N>) -> MathResult<()> {
+        // boost earnings join the staked amount when the position is checkpointed
+        let boost_earnings = self.boost_earnings(position)?;
+        self.boost_reserve = self.boost_reserve.checked_sub(boost_earnings).ok_or(MathError::Underflow)?;
+        self.amount = self.amount.checked_add(boost_earnings).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+
+    fn remove_boost_weight(&mut self, position: &Position<N>) -> MathResult<()> {
+        // must run before the checkpoint moves, it undoes exactly what add_boost_weight added
+        let weight = self.position_boost_weight(position)?;
+        self.total_boost_weight = self.total_boost_weight.checked_sub(weight).ok_or(MathError::Underflow)?;
+        Ok(())
+    }
+
+    fn add_boost_weight(&mut self, position: &mut Position<N>, now: i64) -> MathResult<()> {
+        // the weight only ever follows the time left on the lock when the position is checkpointed, until then it
+        // keeps what it was staked with. Expired locks fall back to base weight
+        position.lock_weight_bps = lock_weight_bps_at(position.lock_end, now)?;
+
+        position.boost_weight = position.balance.checked_mul(position.lock_weight_bps - BASE_WEIGHT_BPS).ok_or(MathError::Overflow)?
+            / BASE_WEIGHT_BPS;
+        position.boost_checkpoint = self.boost.current;
+
+        let weight = self.position_boost_weight(position)?;
+        self.total_boost_weight = self.total_boost_weight.checked_add(weight).ok_or(MathError::Overflow)?;
+        Ok(())
+    }
+}
+
+/// Linear boost from 1x when unlocked up to MAX_WEIGHT_BPS at MAX_LOCK_DURATION
+pub fn calculate_lock_weight_bps(lock_duration: i64) -> MathResult<u64> {
+    let lock_duration = lock_duration.clamp(0, MAX_LOCK_DURATION) as u64;
+
+    BASE_WEIGHT_BPS.checked_add((MAX_WEIGHT_BPS - BASE_WEIGHT_BPS).checked_mul(lock_duration).ok_or(MathError::Overflow)?
+        / MAX_LOCK_DURATION as u64)
+        .ok_or(MathError::Overflow)
+}
+
+/// Lock weight of a lock ending at `lock_end`, from the time left on it at `now`
+pub fn lock_weight_bps_at(lock_end: i64, now: i64) -> MathResult<u64> {
+    if now >= lock_end {
+        return Ok(BASE_WEIGHT_BPS)
+    }
+
+    calculate_lock_weight_bps(lock_end - now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MULT: u64 = 1_000_000;
+
+    #[test]
+    fn distribute_and_burn_settle_pro_rata() {
+        let mut pool = Pool::<0>::new();
+        let mut position1 = pool.open_position();
+        pool.stake(&mut position1, 100 * MULT, 0, 0).unwrap();
+        let mut position2 = pool.open_position();
+        pool.stake(&mut position2, 300 * MULT, 0, 0).unwrap();
+
+        pool.distribute(40 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&position1).unwrap(), 110 * MULT);
+        pool.burn(88 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&position1).unwrap(), 88 * MULT);
+        assert_eq!(pool.settled_balance(&position2).unwrap(), 264 * MULT);
+
+        assert_eq!(pool.unstake(&mut position1, 88 * MULT, 0).unwrap(), 0);
+        assert_eq!(pool.amount, 264 * MULT);
+        assert_eq!(pool.unstake(&mut position2, 265 * MULT, 0), Err(MathError::Overdraw));
+    }
+
+    #[test]
+    fn unstake_all_takes_the_settled_balance() {
+        let mut pool = Pool::<0>::new();
+        let mut position = pool.open_position();
+        pool.stake(&mut position, 100 * MULT, 0, 0).unwrap();
+        let mut other = pool.open_position();
+        pool.stake(&mut other, 200 * MULT, 0, 0).unwrap();
+        pool.distribute(7).unwrap();
+
+        assert_eq!(pool.unstake_amount(&position, 40 * MULT).unwrap(), 40 * MULT);
+        let amount = pool.unstake_amount(&position, UNSTAKE_ALL).unwrap();
+        assert_eq!(amount, pool.settled_balance(&position).unwrap());
+        assert_eq!(pool.unstake(&mut position, amount, 0).unwrap(), 0);
+        assert_eq!(position.balance, 0);
+        assert_eq!(pool.user_deposit_amt, 200 * MULT);
+    }
+
+    #[test]
+    fn locked_position_earns_boosted_share() {
+        let mut pool = Pool::<0>::new();
+        let mut unlocked = pool.open_position();
+        pool.stake(&mut unlocked, 100 * MULT, 0, 0).unwrap();
+        let mut locked = pool.open_position();
+        pool.stake(&mut locked, 100 * MULT, MAX_LOCK_DURATION, 0).unwrap();
+
+        pool.distribute(30 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&unlocked).unwrap(), 110 * MULT);
+        assert_eq!(pool.settled_balance(&locked).unwrap(), 120 * MULT);
+        assert_eq!(pool.amount + pool.boost_reserve, 230 * MULT);
+    }
+
+    #[test]
+    fn poke_drops_expired_boost() {
+        let mut pool = Pool::<0>::new();
+        let mut unlocked = pool.open_position();
+        pool.stake(&mut unlocked, 100 * MULT, 0, 0).unwrap();
+        let mut locked = pool.open_position();
+        pool.stake(&mut locked, 100 * MULT, MAX_LOCK_DURATION, 0).unwrap();
+        assert_eq!(locked.boost_weight, 100 * MULT);
+
+        // nothing to do while the lock still has all its time left
+        assert!(!pool.poke(&mut locked, 0).unwrap());
+
+        // until it's checkpointed an expired lock keeps its boosted share
+        let end = MAX_LOCK_DURATION;
+        pool.distribute(30 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&locked).unwrap(), 120 * MULT);
+
+        assert!(pool.poke(&mut locked, end).unwrap());
+        assert_eq!(locked.lock_weight_bps, BASE_WEIGHT_BPS);
+        assert_eq!(locked.boost_weight, 0);
+        assert_eq!(locked.balance, 120 * MULT);
+        assert_eq!(pool.total_boost_weight, 0);
+        assert!(!pool.poke(&mut locked, end).unwrap());
+
+        // the next distribution is shared by stake alone, 10% each
+        pool.distribute(23 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&unlocked).unwrap(), 121 * MULT);
+        assert_eq!(pool.settled_balance(&locked).unwrap(), 132 * MULT);
+    }
+
+    #[test]
+    fn lock_weight_follows_the_time_left() {
+        assert_eq!(lock_weight_bps_at(MAX_LOCK_DURATION, 0).unwrap(), MAX_WEIGHT_BPS);
+        assert_eq!(lock_weight_bps_at(MAX_LOCK_DURATION, MAX_LOCK_DURATION / 2).unwrap(), 15_000);
+        assert_eq!(lock_weight_bps_at(MAX_LOCK_DURATION, MAX_LOCK_DURATION).unwrap(), BASE_WEIGHT_BPS);
+        assert_eq!(lock_weight_bps_at(0, 1).unwrap(), BASE_WEIGHT_BPS);
+    }
+
+    #[test]
+    fn full_burn_starts_new_epoch() {
+        let mut pool = Pool::<1>::new();
+        let mut old_position = pool.open_position();
+        pool.stake(&mut old_position, 100 * MULT, 0, 0).unwrap();
+        pool.distribute_reward(0, 10 * MULT).unwrap();
+
+        pool.burn(100 * MULT).unwrap();
+        assert_eq!(pool.epoch, 1);
+        assert_eq!(pool.distribution_rate, RATE_PRECISION);
+        assert_eq!(pool.settled_balance(&old_position).unwrap(), 0);
+
+        // new stakers are unaffected by the wipe
+        let mut new_position = pool.open_position();
+        pool.stake(&mut new_position, 50 * MULT, 0, 0).unwrap();
+        pool.distribute(10 * MULT).unwrap();
+        assert_eq!(pool.settled_balance(&new_position).unwrap(), 60 * MULT);
+
+        // the old position keeps the reward mint earnings it had before the wipe
+        pool.settle_rewards(&mut old_position).unwrap();
+        assert_eq!(old_position.rewards_owed[0], 10 * MULT);
+        assert_eq!(pool.unstake(&mut old_position, 0, 0).unwrap(), 0);
+        assert_eq!(pool.user_deposit_amt, 50 * MULT);
+    }
+}
\ No newline at end of file
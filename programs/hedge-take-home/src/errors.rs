@@ -1,4 +1,4 @@
-use anchor_lang::prelude::*;
+use {anchor_lang::prelude::*, staking_math::MathError};
 
 #[error_code]
 pub enum StakeError {
@@ -13,5 +13,77 @@ pub enum StakeError {
     #[msg("Attempted to withdraw more RND rewards than are available")]
     OverdrawError,
     #[msg("Invalid user provided")]
-    InvalidUser
+    InvalidUser,
+    #[msg("Pool vault is already owned by the per-pool vault authority")]
+    VaultAlreadyMigrated,
+    #[msg("Signer is not the pending pool authority")]
+    InvalidPendingAuthority,
+    #[msg("Emission end must not be before emission start")]
+    InvalidEmissionSchedule,
+    #[msg("Pool already has the maximum number of reward mints")]
+    RewardMintLimitReached,
+    #[msg("Reward mint is invalid for this pool")]
+    InvalidRewardMint,
+    #[msg("Reward vault does not match the pool's reward mint")]
+    InvalidRewardVault,
+    #[msg("Nothing is staked in the pool")]
+    ZeroStakedSupply,
+    #[msg("Lock duration is outside the allowed range")]
+    InvalidLockDuration,
+    #[msg("Stake is locked until its lock period ends")]
+    StakeLocked,
+    #[msg("Unbonding period must not be negative")]
+    InvalidUnbondingPeriod,
+    #[msg("Pool has an unbonding period, use request_unstake and withdraw")]
+    UnbondingRequired,
+    #[msg("No tokens are waiting to be withdrawn")]
+    NothingToWithdraw,
+    #[msg("Unbonding period has not ended yet")]
+    UnbondingNotComplete,
+    // no longer returned since events go through anchor's event-cpi, kept so the codes after it don't change
+    #[msg("Event authority or program account is invalid")]
+    InvalidEventAuthority,
+    #[msg("Mathematical underflow occured")]
+    MathematicalUnderflowError,
+    #[msg("Attempted to burn more tokens than are staked in the pool")]
+    BurnExceedsPool,
+    #[msg("Distribution rate is zero, positions can't be settled against it")]
+    InvalidDistributionRate,
+    #[msg("Stake entry still has a balance, unbonding tokens or unclaimed rewards")]
+    StakeEntryNotEmpty,
+    #[msg("Pool has been deactivated")]
+    PoolDeactivated,
+    #[msg("Pool must be deactivated first")]
+    PoolActive,
+    #[msg("Pool still has staked or unbonding tokens")]
+    PoolNotEmpty,
+    #[msg("Instruction is paused for this pool")]
+    Paused,
+    #[msg("Pause flags contain unknown bits")]
+    InvalidPauseFlags,
+    #[msg("Mint has a token extension pools don't support")]
+    InvalidMintExtension,
+    #[msg("Pool accounting isn't covered by the token vault")]
+    InvariantViolation,
+    #[msg("Account is already in the current layout")]
+    AccountAlreadyUpgraded,
+    #[msg("Reward mint earnings haven't all been claimed")]
+    RewardsOutstanding,
+    #[msg("Locking a position needs the beneficiary's signature")]
+    LockNotApproved,
+    #[msg("Token vault or vault authority does not match the pool")]
+    InvalidTokenVault
+}
+
+impl From<MathError> for StakeError {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::Overflow => StakeError::MathematicalOverflowError,
+            MathError::Underflow => StakeError::MathematicalUnderflowError,
+            MathError::ZeroStakedSupply => StakeError::ZeroStakedSupply,
+            MathError::BurnExceedsPool => StakeError::BurnExceedsPool,
+            MathError::InvalidDistributionRate => StakeError::InvalidDistributionRate,
+            MathError::Overdraw => StakeError::OverdrawError
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,175 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct PoolDeactivated {
+    pub pool: Pubkey,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct PoolClosed {
+    pub pool: Pubkey,
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct StakeEntryCreated {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub stake_entry: Pubkey,
+    pub distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct StakeEntryClosed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub stake_entry: Pubkey,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Staked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub lock_end: i64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Unstaked {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Claimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Compounded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub pre_balance: u64,
+    pub balance: u64,
+    pub lock_end: i64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct LockWeightUpdated {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub balance: u64,
+    pub lock_end: i64,
+    pub pre_lock_weight_bps: u64,
+    pub lock_weight_bps: u64,
+    pub total_boost_weight: u128,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct UnstakeRequested {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub unbonding_amount: u64,
+    pub unbonding_end: i64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub pending_withdrawal: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Withdrawn {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub pending_withdrawal: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Distributed {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Reconciled {
+    pub pool: Pubkey,
+    pub vault_amount: u64,
+    pub expected_amount: u64,
+    pub surplus: u64,
+    pub shortfall: u64,
+    pub corrected: bool,
+    pub pool_amount: u64,
+    pub timestamp: i64
+}
+
+#[event]
+pub struct Burned {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub pre_distribution_rate: u128,
+    pub post_distribution_rate: u128,
+    pub pool_amount: u64,
+    pub user_deposit_amt: u64,
+    pub timestamp: i64
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+};
+
+pub fn handler(ctx: Context<AcceptPoolAuthorityCtx>) -> Result<()> {
+
+    // complete the handover started by set_pool_authority
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.authority = ctx.accounts.pending_authority.key();
+    pool_state.pending_authority = Pubkey::default();
+    msg!("New pool authority: {}", pool_state.authority);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptPoolAuthorityCtx<'info> {
+    #[account(
+        constraint = pending_authority.key() == pool_state.pending_authority
+        @ StakeError::InvalidPendingAuthority
+    )]
+    pub pending_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+}
\ No newline at end of file
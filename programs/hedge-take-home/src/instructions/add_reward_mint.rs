@@ -0,0 +1,69 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, utils::*},
+    anchor_spl::token_interface::{TokenAccount, Mint, TokenInterface},
+};
+
+pub fn handler(ctx: Context<AddRewardMintCtx>) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.reward_mint.to_account_info())?;
+    let reward_mint = ctx.accounts.reward_mint.key();
+    let reward_vault = ctx.accounts.reward_vault.key();
+    let reward_vault_bump = *ctx.bumps.get("reward_vault").unwrap();
+
+    // take the first free reward slot, reward vault seeds already rule out duplicates
+    let pool_state = &mut ctx.accounts.pool_state;
+    let reward_info = match pool_state.reward_infos.iter_mut().find(|reward_info| reward_info.mint == Pubkey::default()) {
+        Some(reward_info) => reward_info,
+        None => return err!(StakeError::RewardMintLimitReached)
+    };
+
+    reward_info.mint = reward_mint;
+    reward_info.vault = reward_vault;
+    reward_info.vault_bump = reward_vault_bump;
+    reward_info.reward_rate = 0;
+    reward_info.prev_scale_reward_rate = 0;
+    reward_info.epoch_end_reward_rate = 0;
+    msg!("Reward mint added: {}", reward_mint);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddRewardMintCtx<'info> {
+    #[account(
+        mut,
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        constraint = reward_mint.key() != pool_state.token_mint
+        @ StakeError::InvalidRewardMint
+    )]
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        token::mint = reward_mint,
+        token::authority = vault_authority,
+        seeds = [pool_state.token_mint.key().as_ref(), reward_mint.key().as_ref(), REWARD_VAULT_SEED.as_bytes()],
+        bump,
+        payer = program_authority
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>
+}
\ No newline at end of file
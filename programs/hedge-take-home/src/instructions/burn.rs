@@ -1,14 +1,30 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*, errors::*},
-    anchor_spl::{token::{TokenAccount, Token, Mint, Burn, burn}},
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint, Burn, burn}},
 };
 
 pub fn handler(ctx: Context<BurnCtx>, amount: u64) -> Result<()> {
+    let pre_distribution_rate = ctx.accounts.pool_state.distribution_rate;
+
+    // settle any streamed rewards first so they are burned along with the rest of the pool
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // only staked tokens can be burned, fail before the vault is touched
+    if amount > ctx.accounts.pool_state.amount {
+        return err!(StakeError::BurnExceedsPool)
+    }
 
     // program signer seeds
+    let token_mint = ctx.accounts.pool_state.token_mint;
     let auth_bump = ctx.accounts.pool_state.vault_auth_bump;
-    let auth_seeds = &[VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let auth_seeds = &[token_mint.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
     let signer = &[&auth_seeds[..]];
 
     // burn the tokens
@@ -19,37 +35,32 @@ pub fn handler(ctx: Context<BurnCtx>, amount: u64) -> Result<()> {
     msg!("Tokens to burn: {}", amount);
     msg!("Initial total staked: {}", pool_state.amount);
     msg!("Initial distribution rate: {}", pool_state.distribution_rate);
-
-    if pool_state.amount != 0 {
-        // calculate new distribution rate
-        let new_distribution_rate = RATE_MULT.checked_sub((amount as u128).checked_mul(RATE_MULT).unwrap()
-                                    .checked_div(pool_state.amount as u128).unwrap()).unwrap();
-        msg!("New rate (to be mult by previous: {}", new_distribution_rate);
-
-        if pool_state.distribution_rate == 1 {
-            pool_state.distribution_rate = pool_state.distribution_rate.checked_mul(new_distribution_rate).unwrap();
-        } else {
-            pool_state.distribution_rate = pool_state.distribution_rate.checked_mul(new_distribution_rate).unwrap().checked_div(RATE_MULT).unwrap();
-        }
-        
-        msg!("User deposits: {}", pool_state.user_deposit_amt);
-        msg!("Distribution rate: {}", pool_state.distribution_rate);
-    }
-
-    // update state in pool
-    pool_state.amount = pool_state.amount.checked_sub(amount).unwrap();
+    apply_burn(pool_state, amount)?;
 
     msg!("Current total staked: {}", pool_state.amount);
     msg!("Amount deposited by Users: {}", pool_state.user_deposit_amt);
     msg!("Current distribution rate: {}", pool_state.distribution_rate);
+    check_solvency(&ctx.accounts.pool_state, &mut ctx.accounts.token_vault)?;
+
+    let pool_state = &ctx.accounts.pool_state;
+    emit_event!(ctx, Burned {
+        pool: pool_state.key(),
+        amount,
+        pre_distribution_rate,
+        post_distribution_rate: pool_state.distribution_rate,
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: Clock::get()?.unix_timestamp
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct BurnCtx<'info> {
     #[account(
-        constraint = program_authority.key() == PROGRAM_AUTHORITY
+        constraint = program_authority.key() == pool_state.burner
         @ StakeError::InvalidProgramAuthority
     )]
     pub program_authority: Signer<'info>,
@@ -57,6 +68,8 @@ pub struct BurnCtx<'info> {
         mut,
         seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
         bump = pool_state.bump,
+        constraint = !pool_state.is_paused(PAUSE_BURN)
+        @ StakeError::Paused
     )]
     pub pool_state: Account<'info, PoolState>,
     #[account(
@@ -64,10 +77,10 @@ pub struct BurnCtx<'info> {
         seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
         bump = pool_state.vault_bump,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     /// CHECK: This is not dangerous because we're only using this as a program signer
     #[account(
-        seeds = [VAULT_AUTH_SEED.as_bytes()],
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
         bump = pool_state.vault_auth_bump
     )]
     pub vault_authority: AccountInfo<'info>,
@@ -76,8 +89,8 @@ pub struct BurnCtx<'info> {
         constraint = token_mint.key() == pool_state.token_mint
         @ StakeError::InvalidMint
     )]
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>
 }
 
 impl<'info> BurnCtx <'info> {
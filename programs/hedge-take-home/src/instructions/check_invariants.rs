@@ -0,0 +1,76 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+    anchor_spl::{token_interface::TokenAccount},
+};
+
+pub fn handler(ctx: Context<CheckInvariantsCtx>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    let vault_amount = ctx.accounts.token_vault.amount;
+    let expected_amount = pool_state.expected_vault_amount()?;
+    msg!("Vault balance: {}", vault_amount);
+    msg!("Expected vault balance: {}", expected_amount);
+    if vault_amount < expected_amount {
+        return err!(StakeError::InvariantViolation)
+    }
+
+    // stake entries of the pool can be passed as remaining accounts, whatever they settle to has to
+    // fit in what the pool has staked and what it holds for withdrawals
+    let engine = pool_state.engine();
+    let mut checked_entries: Vec<Pubkey> = Vec::new();
+    let mut settled_total: u128 = 0;
+    let mut unbonding_total: u128 = 0;
+    for entry_info in ctx.remaining_accounts.iter() {
+        let user_entry = Account::<StakeEntry>::try_from(entry_info)?;
+        let entry_address = Pubkey::create_program_address(
+            &[user_entry.user.as_ref(), pool_state.token_mint.as_ref(), STAKE_ENTRY_SEED.as_bytes(), &[user_entry.bump]],
+            ctx.program_id
+        ).map_err(|_| ErrorCode::ConstraintSeeds)?;
+        if entry_address != entry_info.key() {
+            return err!(ErrorCode::ConstraintSeeds)
+        }
+
+        // an entry passed twice is only counted once
+        if checked_entries.contains(&entry_address) {
+            continue
+        }
+        checked_entries.push(entry_address);
+
+        // entries left over from a closed pool at the same address hold nothing in this one
+        if !user_entry.is_current(pool_state) {
+            continue
+        }
+
+        let settled_balance = engine.settled_balance(&user_entry.position()).map_err(StakeError::from)?;
+        settled_total = settled_total.checked_add(settled_balance as u128).ok_or(StakeError::MathematicalOverflowError)?;
+        unbonding_total = unbonding_total.checked_add(user_entry.unbonding_amount as u128).ok_or(StakeError::MathematicalOverflowError)?;
+    }
+
+    if !checked_entries.is_empty() {
+        msg!("Stake entries checked: {}", checked_entries.len());
+        msg!("Settled balances: {}", settled_total);
+        msg!("Unbonding: {}", unbonding_total);
+    }
+    // settled balances round down, so together they can never be more than the pool holds for them
+    if settled_total > (pool_state.amount as u128) + (pool_state.boost_reserve as u128)
+        || unbonding_total > pool_state.pending_withdrawal as u128
+    {
+        return err!(StakeError::InvariantViolation)
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckInvariantsCtx<'info> {
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>
+}
\ No newline at end of file
@@ -0,0 +1,115 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<ClaimCtx>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
+
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    msg!("Principal: {}", ctx.accounts.user_stake_entry.balance);
+
+    // growth above principal is paid out, the principal stays staked and starts earning again from the
+    // current distribution rate. Burns that took the position below principal leave nothing to claim
+    let claim_amount = update_position(
+        &mut ctx.accounts.pool,
+        &mut ctx.accounts.user_stake_entry,
+        |pool, position| pool.claim(position, now)
+    )?;
+    if claim_amount == 0 {
+        msg!("No rewards to claim");
+        return Ok(())
+    }
+    msg!("Rewards claimed: {}", claim_amount);
+
+    // transfer rewards from stake vault to user
+    transfer_from_vault(
+        &ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program,
+        claim_amount
+    )?;
+
+    msg!("Total staked after claim: {}", ctx.accounts.pool.amount);
+
+    let pool = &ctx.accounts.pool;
+    let user_entry = &ctx.accounts.user_stake_entry;
+    emit_event!(ctx, Claimed {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount: claim_amount,
+        balance: user_entry.balance,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_UNSTAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
+        @ StakeError::InvalidUser
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
+}
\ No newline at end of file
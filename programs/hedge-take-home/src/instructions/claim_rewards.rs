@@ -0,0 +1,100 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+    anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, TransferChecked, transfer_checked},
+    solana_program::account_info::next_account_info,
+};
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRewardsCtx<'info>>) -> Result<()> {
+    // bring earnings for every reward mint up to date
+    update_position(&mut ctx.accounts.pool, &mut ctx.accounts.user_stake_entry, |pool, position| pool.settle_rewards(position))?;
+
+    // program signer seeds
+    let token_mint = ctx.accounts.pool.token_mint;
+    let auth_bump = ctx.accounts.pool.vault_auth_bump;
+    let auth_seeds = &[token_mint.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let signer = &[&auth_seeds[..]];
+
+    // remaining accounts are (reward vault, reward mint, user token account) triples in reward slot order
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let pool = &mut ctx.accounts.pool;
+    let user_entry = &mut ctx.accounts.user_stake_entry;
+
+    let reward_infos = pool.reward_infos;
+
+    for (index, reward_info) in reward_infos.iter().enumerate() {
+        if reward_info.mint == Pubkey::default() {
+            continue
+        }
+
+        let reward_vault = next_account_info(remaining_accounts)?;
+        let reward_mint = next_account_info(remaining_accounts)?;
+        let user_reward_account = next_account_info(remaining_accounts)?;
+        if reward_vault.key() != reward_info.vault {
+            return err!(StakeError::InvalidRewardVault)
+        }
+        if reward_mint.key() != reward_info.mint || InterfaceAccount::<TokenAccount>::try_from(user_reward_account)?.mint != reward_info.mint {
+            return err!(StakeError::InvalidMint)
+        }
+
+        // reward mints of the other token program are claimed by calling again with that program
+        if reward_vault.owner != &ctx.accounts.token_program.key() {
+            continue
+        }
+
+        let owed = user_entry.rewards_owed[index];
+        msg!("Claiming {} of reward mint {}", owed, reward_info.mint);
+        if owed == 0 {
+            continue
+        }
+
+        // Token-2022 transfer fees come out of what the user receives
+        let decimals = InterfaceAccount::<Mint>::try_from(reward_mint)?.decimals;
+        let cpi_accounts = TransferChecked {
+            from: reward_vault.clone(),
+            mint: reward_mint.clone(),
+            to: user_reward_account.clone(),
+            authority: ctx.accounts.vault_authority.to_account_info()
+        };
+        transfer_checked(CpiContext::new_with_signer(ctx.accounts.token_program.to_account_info(), cpi_accounts, signer), owed, decimals)?;
+
+        user_entry.rewards_owed[index] = 0;
+        pool.rewards_owed_total[index] = pool.rewards_owed_total[index]
+            .checked_sub(owed).ok_or(StakeError::MathematicalUnderflowError)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewardsCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_UNSTAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
+        @ StakeError::InvalidUser
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
+}
\ No newline at end of file
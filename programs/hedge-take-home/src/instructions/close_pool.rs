@@ -0,0 +1,164 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, Burn, CloseAccount, burn, close_account},
+    solana_program::account_info::next_account_info,
+};
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, ClosePoolCtx<'info>>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+
+    // every user has to be paid out, including tokens still unbonding. amount can still hold rounding dust
+    // from distributions and burns once every entry is empty, nobody is owed that
+    if pool_state.user_deposit_amt != 0 || pool_state.pending_withdrawal != 0 {
+        return err!(StakeError::PoolNotEmpty)
+    }
+
+    // reward mint earnings stay in the entries until they're claimed, the reward vaults can't go before that
+    if pool_state.rewards_owed_total.iter().any(|owed| *owed != 0) {
+        return err!(StakeError::RewardsOutstanding)
+    }
+
+    // program signer seeds
+    let token_mint = pool_state.token_mint;
+    let auth_bump = pool_state.vault_auth_bump;
+    let auth_seeds = &[token_mint.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let signer = &[&auth_seeds[..]];
+
+    // only rounding dust and unreleased boost reserve can be left, nobody is owed it
+    let dust = ctx.accounts.token_vault.amount;
+    if dust != 0 {
+        msg!("Burning vault dust: {}", dust);
+        burn(ctx.accounts.burn_ctx().with_signer(signer), dust)?;
+    }
+
+    // Token-2022 vaults can't be closed while they hold withheld transfer fees, those go back to the mint
+    harvest_withheld_fees(
+        &ctx.accounts.token_program.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.token_vault.to_account_info()
+    )?;
+
+    // vault rent goes to the authority here, anchor closes the pool state account after the handler
+    close_account(ctx.accounts.close_ctx().with_signer(signer))?;
+
+    // remaining accounts are (reward vault, reward mint) pairs in reward slot order
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+
+    for reward_info in pool_state.reward_infos.iter() {
+        if reward_info.mint == Pubkey::default() {
+            continue
+        }
+
+        let reward_vault = next_account_info(remaining_accounts)?;
+        let reward_mint = next_account_info(remaining_accounts)?;
+        if reward_vault.key() != reward_info.vault {
+            return err!(StakeError::InvalidRewardVault)
+        }
+        if reward_mint.key() != reward_info.mint {
+            return err!(StakeError::InvalidMint)
+        }
+
+        // a reward mint can belong to either token program, whichever owns its vault
+        let reward_token_program = if reward_vault.owner == &ctx.accounts.token_program.key() {
+            ctx.accounts.token_program.to_account_info()
+        } else {
+            ctx.accounts.reward_token_program.to_account_info()
+        };
+
+        // every claim is paid, only rounding dust from reward distributions is left
+        let reward_dust = InterfaceAccount::<TokenAccount>::try_from(reward_vault)?.amount;
+        if reward_dust != 0 {
+            msg!("Burning dust of reward mint {}: {}", reward_info.mint, reward_dust);
+            let cpi_accounts = Burn {
+                mint: reward_mint.clone(),
+                from: reward_vault.clone(),
+                authority: ctx.accounts.vault_authority.to_account_info()
+            };
+            burn(CpiContext::new_with_signer(reward_token_program.clone(), cpi_accounts, signer), reward_dust)?;
+        }
+        harvest_withheld_fees(&reward_token_program, reward_mint, reward_vault)?;
+
+        let cpi_accounts = CloseAccount {
+            account: reward_vault.clone(),
+            destination: ctx.accounts.program_authority.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info()
+        };
+        close_account(CpiContext::new_with_signer(reward_token_program, cpi_accounts, signer))?;
+    }
+    msg!("Pool closed");
+
+    emit_event!(ctx, PoolClosed {
+        pool: pool_state.key(),
+        token_mint,
+        authority: pool_state.authority,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePoolCtx<'info> {
+    #[account(
+        mut,
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        close = program_authority,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = pool_state.deactivated
+        @ StakeError::PoolActive
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Closes reward vaults that don't belong to the staked mint's token program
+    pub reward_token_program: Interface<'info, TokenInterface>
+}
+
+impl<'info> ClosePoolCtx <'info> {
+    pub fn burn_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Burn {
+            mint: self.token_mint.to_account_info(),
+            from: self.token_vault.to_account_info(),
+            authority: self.vault_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    pub fn close_ctx(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = CloseAccount {
+            account: self.token_vault.to_account_info(),
+            destination: self.program_authority.to_account_info(),
+            authority: self.vault_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,184 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<CloseStakeEntryCtx>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // reward mint earnings would be lost with the entry, they have to be claimed first. close_pool only
+    // goes through once every claim is paid, so entries of a closed pool have nothing left to settle. That
+    // includes entries of a closed pool whose address was initialized again, they're from an older generation
+    let mut live_pool = None;
+    if ctx.accounts.pool.owner == &crate::ID {
+        let pool = Account::<PoolState>::try_from(&ctx.accounts.pool)?;
+        if ctx.accounts.user_stake_entry.is_current(&pool) {
+            live_pool = Some(pool);
+        }
+    }
+
+    // whatever is still staked or done unbonding is paid out before the entry closes, so nobody can keep
+    // it open by staking dust into it with stake_for. The same locks, cooldown and pause as unstake and withdraw apply
+    let mut unstaked = None;
+    let mut withdrawn = None;
+    let accounts = &mut *ctx.accounts;
+    let has_tokens = accounts.user_stake_entry.balance != 0 || accounts.user_stake_entry.unbonding_amount != 0;
+    if let (Some(pool), true) = (live_pool.as_mut(), has_tokens) {
+        if pool.is_paused(PAUSE_UNSTAKE) {
+            return err!(StakeError::Paused)
+        }
+
+        // a closed pool has no vault left, so the accounts to pay out of it are optional
+        let (token_vault, user_token_account, vault_authority, token_program) = match (
+            accounts.token_vault.as_mut(),
+            accounts.user_token_account.as_ref(),
+            accounts.vault_authority.as_ref(),
+            accounts.token_program.as_ref()
+        ) {
+            (Some(token_vault), Some(user_token_account), Some(vault_authority), Some(token_program)) =>
+                (token_vault, user_token_account, vault_authority, token_program),
+            _ => return err!(StakeError::StakeEntryNotEmpty)
+        };
+        if token_vault.key() != pool.token_vault || vault_authority.key() != pool.vault_authority {
+            return err!(StakeError::InvalidTokenVault)
+        }
+        if user_token_account.mint != pool.token_mint {
+            return err!(StakeError::InvalidMint)
+        }
+        let token_mint = InterfaceAccount::<Mint>::try_from(&accounts.token_mint)?;
+        let user_entry = &mut accounts.user_stake_entry;
+
+        if user_entry.balance != 0 {
+            if now < user_entry.lock_end {
+                return err!(StakeError::StakeLocked)
+            }
+            // pools with a cooldown only release tokens through request_unstake and withdraw
+            if pool.unbonding_period != 0 {
+                return err!(StakeError::UnbondingRequired)
+            }
+
+            let pre_distribution_rate = pool.distribution_rate;
+            accrue_emissions(pool, token_vault, &token_mint, vault_authority, token_program)?;
+            let amount = update_position(pool, user_entry, |pool, position| {
+                let amount = pool.unstake_amount(position, UNSTAKE_ALL)?;
+                pool.unstake(position, amount, now)?;
+                Ok(amount)
+            })?;
+            msg!("Amount unstaked: {}", amount);
+            transfer_from_vault(pool, token_vault, &token_mint, &user_token_account.to_account_info(), vault_authority, token_program, amount)?;
+
+            unstaked = Some(Unstaked {
+                pool: pool.key(),
+                user: user_entry.user,
+                amount,
+                balance: user_entry.balance,
+                pre_distribution_rate,
+                post_distribution_rate: pool.distribution_rate,
+                pool_amount: pool.amount,
+                user_deposit_amt: pool.user_deposit_amt,
+                timestamp: now
+            });
+        }
+
+        if user_entry.unbonding_amount != 0 {
+            if now < user_entry.unbonding_end {
+                return err!(StakeError::UnbondingNotComplete)
+            }
+
+            let amount = user_entry.unbonding_amount;
+            msg!("Amount withdrawn: {}", amount);
+            transfer_from_vault(pool, token_vault, &token_mint, &user_token_account.to_account_info(), vault_authority, token_program, amount)?;
+            pool.pending_withdrawal = pool.pending_withdrawal.checked_sub(amount).ok_or(StakeError::MathematicalUnderflowError)?;
+            user_entry.unbonding_amount = 0;
+            user_entry.unbonding_end = 0;
+
+            withdrawn = Some(Withdrawn {
+                pool: pool.key(),
+                user: user_entry.user,
+                amount,
+                distribution_rate: pool.distribution_rate,
+                pool_amount: pool.amount,
+                user_deposit_amt: pool.user_deposit_amt,
+                pending_withdrawal: pool.pending_withdrawal,
+                timestamp: now
+            });
+        }
+        check_solvency(pool, token_vault)?;
+    }
+
+    // a closed pool's entry, or one without the accounts to pay it out, has to be emptied with unstake and withdraw first
+    let user_entry = &ctx.accounts.user_stake_entry;
+    if user_entry.balance != 0 || user_entry.unbonding_amount != 0 {
+        return err!(StakeError::StakeEntryNotEmpty)
+    }
+
+    let mut position = user_entry.position();
+    if let Some(pool) = &live_pool {
+        pool.engine().settle_rewards(&mut position).map_err(StakeError::from)?;
+    }
+    if position.rewards_owed.iter().any(|owed| *owed != 0) {
+        return err!(StakeError::StakeEntryNotEmpty)
+    }
+
+    // the pool is only deserialized in the handler, so anchor won't write it back on its own
+    if let Some(pool) = &live_pool {
+        pool.exit(&crate::ID)?;
+    }
+
+    if let Some(event) = unstaked {
+        emit_event!(ctx, event);
+    }
+    if let Some(event) = withdrawn {
+        emit_event!(ctx, event);
+    }
+
+    // the rent goes back to the user when anchor closes the entry, the same seeds can be initialized again afterwards
+    emit_event!(ctx, StakeEntryClosed {
+        pool: ctx.accounts.pool.key(),
+        user: user_entry.user,
+        stake_entry: user_entry.key(),
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseStakeEntryCtx <'info> {
+    /// CHECK: The pool can already be closed, a live one is deserialized in the handler
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
+    /// CHECK: This is not dangerous because we're only using this for the pool and entry seeds, it's deserialized in
+    /// the handler when tokens are paid out
+    #[account(mut)]
+    pub token_mint: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
+        @ StakeError::InvalidUser
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        close = user,
+        seeds = [user_stake_entry.user.as_ref(), token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Only to pay out a balance or unbonded tokens left in the entry, checked against the pool in the handler
+    #[account(mut)]
+    pub token_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: This is not dangerous because it's checked against the pool's vault authority in the handler
+    pub vault_authority: Option<UncheckedAccount<'info>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>
+}
\ No newline at end of file
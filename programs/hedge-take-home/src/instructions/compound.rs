@@ -0,0 +1,91 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<CompoundCtx>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
+
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // nothing to roll over for an empty position
+    if ctx.accounts.user_stake_entry.balance == 0 {
+        msg!("No staked balance to compound");
+        return Ok(())
+    }
+
+    // tokens never leave the vault, only the books change. Expired locks drop back to base weight here
+    let pre_balance = ctx.accounts.user_stake_entry.balance;
+    update_position(
+        &mut ctx.accounts.pool,
+        &mut ctx.accounts.user_stake_entry,
+        |pool, position| pool.compound(position, now)
+    )?;
+    msg!("User stake balance: {}", ctx.accounts.user_stake_entry.balance);
+
+    let pool = &ctx.accounts.pool;
+    let user_entry = &ctx.accounts.user_stake_entry;
+    emit_event!(ctx, Compounded {
+        pool: pool.key(),
+        user: user_entry.user,
+        pre_balance,
+        balance: user_entry.balance,
+        lock_end: user_entry.lock_end,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CompoundCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_STAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    // permissionless so positions can be compounded by a crank, the entry is still tied to its owner's seeds
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<CrankEmissionsCtx>) -> Result<()> {
+
+    // anyone can push streamed rewards into the pool, no signer needed
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    msg!("Distribution rate: {}", ctx.accounts.pool_state.distribution_rate);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CrankEmissionsCtx<'info> {
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = !pool_state.is_paused(PAUSE_DISTRIBUTE)
+        @ StakeError::Paused
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
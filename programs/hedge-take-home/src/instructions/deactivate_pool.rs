@@ -0,0 +1,72 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<DeactivatePoolCtx>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // pay out everything streamed so far, nothing streams after this
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // no new stakes or distributions from here on, users drain the pool through unstake or request_unstake and withdraw
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.deactivated = true;
+    pool_state.emission_rate = 0;
+    pool_state.emission_end = pool_state.emission_end.min(now);
+    msg!("Pool deactivated");
+    msg!("Total staked: {}", pool_state.amount);
+
+    emit_event!(ctx, PoolDeactivated {
+        pool: pool_state.key(),
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeactivatePoolCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = !pool_state.deactivated
+        @ StakeError::PoolDeactivated
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
@@ -1,50 +1,53 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*, errors::*},
-    anchor_spl::{token::{TokenAccount, MintTo, Token, Mint, mint_to}},
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, MintTo, TokenInterface, Mint, mint_to}},
 };
 
 pub fn handler(ctx: Context<DistributeCtx>, amount: u64) -> Result<()> {
+    let pre_distribution_rate = ctx.accounts.pool_state.distribution_rate;
+
+    // settle any streamed rewards first so they are shared at the old stake weights
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.mint_auth,
+        &ctx.accounts.token_program
+    )?;
 
     // program signer seeds
+    let token_mint = ctx.accounts.pool_state.token_mint;
     let auth_bump = ctx.accounts.pool_state.vault_auth_bump;
-    let auth_seeds = &[VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let auth_seeds = &[token_mint.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
     let signer = &[&auth_seeds[..]];
 
     // donate RND by minting to vault
     mint_to(ctx.accounts.mint_ctx().with_signer(signer), amount)?;
 
     // update state
-    let pool_state = &mut ctx.accounts.pool_state;
-    if pool_state.amount != 0 {
-        // calculate new distribution rate
-        let new_reward_rate = RATE_MULT.checked_add((amount as u128).checked_mul(RATE_MULT).unwrap()
-                                    .checked_div(pool_state.amount as u128).unwrap()).unwrap();
-        msg!("New rate (to be mult by previous: {}", new_reward_rate);
-
-        if pool_state.distribution_rate == 1 {
-            pool_state.distribution_rate = pool_state.distribution_rate.checked_mul(new_reward_rate).unwrap();
-        } else {
-            pool_state.distribution_rate = pool_state.distribution_rate.checked_mul(new_reward_rate).unwrap().checked_div(RATE_MULT).unwrap();
-        }  
-
-        msg!("Rewards to distribute: {}", amount);
-        msg!("Total staked: {}", pool_state.amount);
-        msg!("User deposits: {}", pool_state.user_deposit_amt);
-        msg!("Distribution rate: {}", pool_state.distribution_rate);
-    }
+    apply_distribution(&mut ctx.accounts.pool_state, amount)?;
+    check_solvency(&ctx.accounts.pool_state, &mut ctx.accounts.token_vault)?;
 
-    // update pool amount
-    pool_state.amount = pool_state.amount.checked_add(amount).unwrap();
-    msg!("Total staked after distribution: {}", pool_state.amount);
+    let pool_state = &ctx.accounts.pool_state;
+    emit_event!(ctx, Distributed {
+        pool: pool_state.key(),
+        amount,
+        pre_distribution_rate,
+        post_distribution_rate: pool_state.distribution_rate,
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: Clock::get()?.unix_timestamp
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct DistributeCtx<'info> {
     #[account(
-        constraint = program_authority.key() == PROGRAM_AUTHORITY
+        constraint = program_authority.key() == pool_state.distributor
         @ StakeError::InvalidProgramAuthority
     )]
     pub program_authority: Signer<'info>,
@@ -52,6 +55,10 @@ pub struct DistributeCtx<'info> {
         mut,
         seeds = [token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
         bump = pool_state.bump,
+        constraint = !pool_state.deactivated
+        @ StakeError::PoolDeactivated,
+        constraint = !pool_state.is_paused(PAUSE_DISTRIBUTE)
+        @ StakeError::Paused
     )]
     pub pool_state: Account<'info, PoolState>,
     #[account(
@@ -59,20 +66,20 @@ pub struct DistributeCtx<'info> {
         seeds = [token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
         bump = pool_state.vault_bump,
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         constraint = token_mint.key() == pool_state.token_mint
         @ StakeError::InvalidMint
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     /// CHECK: This is not dangerous because using as program signer
     #[account(
         constraint = mint_auth.key() == pool_state.vault_authority
         @ StakeError::InvalidMintAuthority
     )]
     pub mint_auth: AccountInfo<'info>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 impl<'info> DistributeCtx <'info> {
@@ -0,0 +1,76 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+    anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint, TransferChecked, transfer_checked},
+};
+
+pub fn handler(ctx: Context<DistributeRewardCtx>, amount: u64) -> Result<()> {
+    if ctx.accounts.pool_state.amount == 0 {
+        return err!(StakeError::ZeroStakedSupply)
+    }
+
+    let index = match ctx.accounts.pool_state.reward_infos.iter().position(|reward_info| reward_info.mint == ctx.accounts.reward_mint.key()) {
+        Some(index) => index,
+        None => return err!(StakeError::InvalidRewardMint)
+    };
+
+    // reward tokens aren't ours to mint, the distributor funds the reward vault directly. Only what reaches
+    // the vault after Token-2022 transfer fees is distributed
+    let pre_vault_amount = ctx.accounts.reward_vault.amount;
+    transfer_checked(ctx.accounts.transfer_ctx(), amount, ctx.accounts.reward_mint.decimals)?;
+    ctx.accounts.reward_vault.reload()?;
+    let amount = ctx.accounts.reward_vault.amount.checked_sub(pre_vault_amount).ok_or(StakeError::MathematicalUnderflowError)?;
+    msg!("Rewards to distribute: {}", amount);
+
+    update_pool(&mut ctx.accounts.pool_state, |pool| pool.distribute_reward(index, amount))?;
+    msg!("Reward rate: {}", ctx.accounts.pool_state.reward_infos[index].reward_rate);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DistributeRewardCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.distributor
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = !pool_state.deactivated
+        @ StakeError::PoolDeactivated,
+        constraint = !pool_state.is_paused(PAUSE_DISTRIBUTE)
+        @ StakeError::Paused
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    pub reward_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), reward_mint.key().as_ref(), REWARD_VAULT_SEED.as_bytes()],
+        bump,
+    )]
+    pub reward_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == reward_mint.key()
+        @ StakeError::InvalidMint
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>
+}
+
+impl<'info> DistributeRewardCtx <'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = TransferChecked {
+            from: self.funder_token_account.to_account_info(),
+            mint: self.reward_mint.to_account_info(),
+            to: self.reward_vault.to_account_info(),
+            authority: self.program_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
\ No newline at end of file
@@ -1,10 +1,11 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*, errors::*},
-    anchor_spl::{token::{TokenAccount, Mint, Token}},
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, Mint, TokenInterface}},
 };
 
 pub fn handler(ctx: Context<InitializePool>) -> Result<()> {
+    check_mint_extensions(&ctx.accounts.token_mint.to_account_info())?;
 
     // initialize pool state
     let pool_state = &mut ctx.accounts.pool_state;
@@ -14,16 +15,50 @@ pub fn handler(ctx: Context<InitializePool>) -> Result<()> {
     pool_state.user_deposit_amt = 0;
     pool_state.token_vault = ctx.accounts.token_vault.key();
     pool_state.token_mint = ctx.accounts.token_mint.key();
-    pool_state.initialized_at = Clock::get().unwrap().unix_timestamp;
+    pool_state.initialized_at = Clock::get()?.unix_timestamp;
+    // close_pool frees the address, entries opened before then are told apart by the generation
+    pool_state.generation = Clock::get()?.slot;
     pool_state.vault_bump = *ctx.bumps.get("token_vault").unwrap();
     pool_state.vault_auth_bump = *ctx.bumps.get("vault_authority").unwrap();
     pool_state.vault_authority = ctx.accounts.vault_authority.key();
-    pool_state.distribution_rate = 1;
+    pool_state.distribution_rate = RATE_PRECISION;
+    pool_state.rate_scale = 0;
+    pool_state.prev_scale_boost_rate = 0;
+    pool_state.epoch = 0;
+    pool_state.epoch_end_rate_scale = 0;
+    pool_state.epoch_end_boost_rate = 0;
+    pool_state.pending_authority = Pubkey::default();
+    pool_state.emission_rate = 0;
+    pool_state.emission_start = 0;
+    pool_state.emission_end = 0;
+    pool_state.last_emission_update = pool_state.initialized_at;
+    pool_state.boost_rate = 0;
+    pool_state.total_boost_weight = 0;
+    pool_state.boost_reserve = 0;
+    pool_state.unbonding_period = 0;
+    pool_state.pending_withdrawal = 0;
+    pool_state.deactivated = false;
+    pool_state.paused = 0;
+    // every role starts with the pool authority, set_pool_roles hands them out
+    pool_state.distributor = pool_state.authority;
+    pool_state.burner = pool_state.authority;
+    pool_state.guardian = pool_state.authority;
+
+    emit_event!(ctx, PoolInitialized {
+        pool: pool_state.key(),
+        token_mint: pool_state.token_mint,
+        authority: pool_state.authority,
+        distribution_rate: pool_state.distribution_rate,
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: pool_state.initialized_at
+    });
 
     Ok(())
 }
 
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializePool<'info> {
     #[account(
@@ -42,9 +77,9 @@ pub struct InitializePool<'info> {
         bump,
         payer = program_authority
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(mut)]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         constraint = program_authority.key() == PROGRAM_AUTHORITY
@@ -53,12 +88,12 @@ pub struct InitializePool<'info> {
     pub program_authority: Signer<'info>,
     /// CHECK: This is not dangerous because we're only using this as a program signer
     #[account(
-        seeds = [VAULT_AUTH_SEED.as_bytes()],
+        seeds = [token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
         bump
     )]
     pub vault_authority: AccountInfo<'info>,
     
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>
 }
\ No newline at end of file
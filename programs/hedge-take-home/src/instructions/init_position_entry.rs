@@ -0,0 +1,109 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, events::*, utils::*},
+    anchor_spl::{
+        associated_token::AssociatedToken,
+        token_interface::{Mint, TokenInterface, TokenAccount, MintTo, SetAuthority, mint_to, set_authority},
+        token_2022::spl_token_2022::instruction::AuthorityType,
+    },
+};
+
+pub fn handler(ctx: Context<InitPositionEntryCtx>) -> Result<()> {
+
+    // the entry belongs to whoever holds the position token, the position mint stands in for the user
+    let position_mint = ctx.accounts.position_mint.key();
+    let user_entry = &mut ctx.accounts.user_stake_entry;
+    user_entry.open(position_mint, *ctx.bumps.get("user_stake_entry").unwrap(), &ctx.accounts.pool_state);
+    user_entry.position_mint = position_mint;
+    msg!("Position mint: {}", position_mint);
+
+    // program signer seeds
+    let token_mint = ctx.accounts.pool_state.token_mint;
+    let auth_bump = ctx.accounts.pool_state.vault_auth_bump;
+    let auth_seeds = &[token_mint.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let signer = &[&auth_seeds[..]];
+
+    // mint the only position token to the user, then drop the mint authority so there can never be a second one
+    mint_to(ctx.accounts.mint_ctx().with_signer(signer), 1)?;
+    set_authority(ctx.accounts.set_authority_ctx().with_signer(signer), AuthorityType::MintTokens, None)?;
+
+    let pool_state = &ctx.accounts.pool_state;
+    let user_entry = &ctx.accounts.user_stake_entry;
+    emit_event!(ctx, StakeEntryCreated {
+        pool: pool_state.key(),
+        user: user_entry.user,
+        stake_entry: user_entry.key(),
+        distribution_rate: pool_state.distribution_rate,
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitPositionEntryCtx <'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init,
+        payer = user,
+        mint::decimals = 0,
+        mint::authority = vault_authority
+    )]
+    pub position_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = position_mint,
+        associated_token::authority = user
+    )]
+    pub position_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        seeds = [position_mint.key().as_ref(), pool_state.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump,
+        payer = user,
+        space = STAKE_ENTRY_SIZE
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitPositionEntryCtx <'info> {
+    pub fn mint_ctx(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = MintTo {
+            mint: self.position_mint.to_account_info(),
+            to: self.position_token_account.to_account_info(),
+            authority: self.vault_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    pub fn set_authority_ctx(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = SetAuthority {
+            current_authority: self.vault_authority.to_account_info(),
+            account_or_mint: self.position_mint.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
\ No newline at end of file
@@ -1,20 +1,29 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*},
+    crate::{state::*, events::*, utils::*},
 };
 
 pub fn handler(ctx: Context<InitEntryCtx>) -> Result<()> {
 
     // initialize user stake entry state
     let user_entry = &mut ctx.accounts.user_stake_entry;
-    user_entry.user = ctx.accounts.user.key();
-    user_entry.bump = *ctx.bumps.get("user_stake_entry").unwrap();
-    user_entry.balance = 0;
-    user_entry.initial_distribution_rate = ctx.accounts.pool_state.distribution_rate;
+    user_entry.open(ctx.accounts.user.key(), *ctx.bumps.get("user_stake_entry").unwrap(), &ctx.accounts.pool_state);
+
+    let pool_state = &ctx.accounts.pool_state;
+    emit_event!(ctx, StakeEntryCreated {
+        pool: pool_state.key(),
+        user: user_entry.user,
+        stake_entry: user_entry.key(),
+        distribution_rate: pool_state.distribution_rate,
+        pool_amount: pool_state.amount,
+        user_deposit_amt: pool_state.user_deposit_amt,
+        timestamp: Clock::get()?.unix_timestamp
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitEntryCtx <'info> {
     #[account(mut)]
@@ -0,0 +1,131 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+    anchor_spl::{token::{TokenAccount, Token, Mint, Transfer, CloseAccount, SetAuthority, transfer, close_account, set_authority, spl_token::instruction::AuthorityType}},
+    solana_program::program_option::COption,
+};
+
+pub fn handler(ctx: Context<MigrateVaultAuthorityCtx>) -> Result<()> {
+
+    // legacy program-wide signer seeds
+    let legacy_auth_bump = ctx.accounts.pool_state.vault_auth_bump;
+    let legacy_auth_seeds = &[VAULT_AUTH_SEED.as_bytes(), &[legacy_auth_bump]];
+    let legacy_signer = &[&legacy_auth_seeds[..]];
+
+    // move everything staked into the vault owned by the per-pool authority
+    let vault_amount = ctx.accounts.legacy_token_vault.amount;
+    msg!("Tokens to migrate: {}", vault_amount);
+    transfer(ctx.accounts.transfer_ctx().with_signer(legacy_signer), vault_amount)?;
+
+    // legacy vault is empty now, return its rent to the program authority
+    close_account(ctx.accounts.close_ctx().with_signer(legacy_signer))?;
+
+    // hand the mint authority over so distribute can keep minting rewards
+    if ctx.accounts.token_mint.mint_authority == COption::Some(ctx.accounts.legacy_vault_authority.key()) {
+        set_authority(
+            ctx.accounts.set_authority_ctx().with_signer(legacy_signer),
+            AuthorityType::MintTokens,
+            Some(ctx.accounts.vault_authority.key())
+        )?;
+        msg!("Mint authority moved to: {}", ctx.accounts.vault_authority.key());
+    }
+
+    // point pool state at the new vault and authority
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.token_vault = ctx.accounts.token_vault.key();
+    pool_state.vault_bump = *ctx.bumps.get("token_vault").unwrap();
+    pool_state.vault_auth_bump = *ctx.bumps.get("vault_authority").unwrap();
+    pool_state.vault_authority = ctx.accounts.vault_authority.key();
+    msg!("New token vault: {}", pool_state.token_vault);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateVaultAuthorityCtx<'info> {
+    #[account(
+        mut,
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = pool_state.vault_authority == legacy_vault_authority.key()
+        @ StakeError::VaultAlreadyMigrated
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), legacy_vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump
+    )]
+    pub legacy_token_vault: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub legacy_vault_authority: AccountInfo<'info>,
+    #[account(
+        init,
+        token::mint = token_mint,
+        token::authority = vault_authority,
+        seeds = [token_mint.key().as_ref(), vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump,
+        payer = program_authority
+    )]
+    pub token_vault: Account<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>
+}
+
+impl<'info> MigrateVaultAuthorityCtx <'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = Transfer {
+            from: self.legacy_token_vault.to_account_info(),
+            to: self.token_vault.to_account_info(),
+            authority: self.legacy_vault_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    pub fn close_ctx(&self) -> CpiContext<'_, '_, '_, 'info, CloseAccount<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = CloseAccount {
+            account: self.legacy_token_vault.to_account_info(),
+            destination: self.program_authority.to_account_info(),
+            authority: self.legacy_vault_authority.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+
+    pub fn set_authority_ctx(&self) -> CpiContext<'_, '_, '_, 'info, SetAuthority<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = SetAuthority {
+            current_authority: self.legacy_vault_authority.to_account_info(),
+            account_or_mint: self.token_mint.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
\ No newline at end of file
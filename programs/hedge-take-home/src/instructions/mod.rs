@@ -1,14 +1,66 @@
 pub mod init_pool;
 pub mod init_stake_entry;
+pub mod init_position_entry;
 pub mod stake;
 pub mod unstake;
 pub mod distribute;
 pub mod burn;
+pub mod migrate_vault_authority;
+pub mod set_pool_authority;
+pub mod accept_pool_authority;
+pub mod set_emissions;
+pub mod crank_emissions;
+pub mod add_reward_mint;
+pub mod distribute_reward;
+pub mod claim_rewards;
+pub mod claim;
+pub mod compound;
+pub mod set_unbonding_period;
+pub mod request_unstake;
+pub mod withdraw;
+pub mod view_position;
+pub mod close_stake_entry;
+pub mod deactivate_pool;
+pub mod close_pool;
+pub mod set_pause;
+pub mod set_pool_roles;
+pub mod reconcile;
+pub mod check_invariants;
+pub mod stake_for;
+pub mod upgrade_pool_state;
+pub mod upgrade_stake_entry;
+pub mod poke_position;
 
 
 pub use init_pool::*;
 pub use init_stake_entry::*;
+pub use init_position_entry::*;
 pub use stake::*;
 pub use unstake::*;
 pub use distribute::*;
-pub use burn::*;
\ No newline at end of file
+pub use burn::*;
+pub use migrate_vault_authority::*;
+pub use set_pool_authority::*;
+pub use accept_pool_authority::*;
+pub use set_emissions::*;
+pub use crank_emissions::*;
+pub use add_reward_mint::*;
+pub use distribute_reward::*;
+pub use claim_rewards::*;
+pub use claim::*;
+pub use compound::*;
+pub use set_unbonding_period::*;
+pub use request_unstake::*;
+pub use withdraw::*;
+pub use view_position::*;
+pub use close_stake_entry::*;
+pub use deactivate_pool::*;
+pub use close_pool::*;
+pub use set_pause::*;
+pub use set_pool_roles::*;
+pub use reconcile::*;
+pub use check_invariants::*;
+pub use stake_for::*;
+pub use upgrade_pool_state::*;
+pub use upgrade_stake_entry::*;
+pub use poke_position::*;
\ No newline at end of file
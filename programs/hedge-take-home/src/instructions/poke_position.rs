@@ -0,0 +1,89 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<PokePositionCtx>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // bring distribution rate up to date with streamed rewards, they were earned at the old weight
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // a lock keeps the weight it was staked with until the position is checkpointed. Poking compounds it,
+    // so a lock that has run down or ended stops taking a boosted share of what is distributed next
+    let pre_lock_weight_bps = ctx.accounts.user_stake_entry.lock_weight_bps;
+    let poked = update_position(
+        &mut ctx.accounts.pool,
+        &mut ctx.accounts.user_stake_entry,
+        |pool, position| pool.poke(position, now)
+    )?;
+    if !poked {
+        msg!("Lock weight is up to date");
+        return Ok(())
+    }
+
+    let pool = &ctx.accounts.pool;
+    let user_entry = &ctx.accounts.user_stake_entry;
+    msg!("Lock weight: {} -> {}", pre_lock_weight_bps, user_entry.lock_weight_bps);
+    msg!("Total boost weight: {}", pool.total_boost_weight);
+
+    emit_event!(ctx, LockWeightUpdated {
+        pool: pool.key(),
+        user: user_entry.user,
+        balance: user_entry.balance,
+        lock_end: user_entry.lock_end,
+        pre_lock_weight_bps,
+        lock_weight_bps: user_entry.lock_weight_bps,
+        total_boost_weight: pool.total_boost_weight,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PokePositionCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_STAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    // permissionless so anyone can take an outdated boost off a position, the entry is still tied to its owner's seeds
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
@@ -0,0 +1,67 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::TokenAccount},
+};
+
+pub fn handler(ctx: Context<ReconcileCtx>, correct: bool) -> Result<()> {
+    // anyone can check the books, only the pool authority can change them
+    if correct && ctx.accounts.caller.key() != ctx.accounts.pool_state.authority {
+        return err!(StakeError::InvalidProgramAuthority)
+    }
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    let vault_amount = ctx.accounts.token_vault.amount;
+    let expected_amount = pool_state.expected_vault_amount()?;
+    let surplus = vault_amount.saturating_sub(expected_amount);
+    let shortfall = expected_amount.saturating_sub(vault_amount);
+    msg!("Vault balance: {}", vault_amount);
+    msg!("Expected vault balance: {}", expected_amount);
+    msg!("Surplus: {}", surplus);
+    msg!("Shortfall: {}", shortfall);
+
+    // surplus tokens are already in the vault, they are shared among stakers like a distribution.
+    // a shortfall is taken from stakers like a burn, the tokens are already gone
+    let mut corrected = false;
+    if correct && surplus != 0 {
+        if pool_state.amount == 0 {
+            msg!("Nothing staked, surplus left in the vault");
+        } else {
+            apply_distribution(pool_state, surplus)?;
+            corrected = true;
+        }
+    } else if correct && shortfall != 0 {
+        apply_burn(pool_state, shortfall)?;
+        corrected = true;
+    }
+
+    emit_event!(ctx, Reconciled {
+        pool: pool_state.key(),
+        vault_amount,
+        expected_amount,
+        surplus,
+        shortfall,
+        corrected,
+        pool_amount: pool_state.amount,
+        timestamp: Clock::get()?.unix_timestamp
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReconcileCtx<'info> {
+    pub caller: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>
+}
\ No newline at end of file
@@ -0,0 +1,112 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<RequestUnstakeCtx>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if now < ctx.accounts.user_stake_entry.lock_end {
+        return err!(StakeError::StakeLocked)
+    }
+
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
+
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    msg!("Amount to unbond: {}", amount);
+
+    // user can only unbond up to their settled balance after rewards/burns, any remaining
+    // balance stays staked from the current distribution rate. UNSTAKE_ALL takes all of it
+    let pool = &mut ctx.accounts.pool;
+    let user_entry = &mut ctx.accounts.user_stake_entry;
+    let amount = update_position(pool, user_entry, |pool, position| {
+        let amount = pool.unstake_amount(position, amount)?;
+        pool.unstake(position, amount, now)?;
+        Ok(amount)
+    })?;
+    msg!("Amount unbonded: {}", amount);
+    user_entry.last_staked = now;
+
+    // unbonding tokens leave the staked total so they stop earning rewards and can't be burned
+    pool.pending_withdrawal = pool.pending_withdrawal.checked_add(amount).ok_or(StakeError::MathematicalOverflowError)?;
+    msg!("Total staked: {}", pool.amount);
+    msg!("Total pending withdrawal: {}", pool.pending_withdrawal);
+
+    // a new request restarts the cooldown for everything the user has unbonding
+    user_entry.unbonding_amount = user_entry.unbonding_amount.checked_add(amount).ok_or(StakeError::MathematicalOverflowError)?;
+    user_entry.unbonding_end = now.checked_add(pool.unbonding_period).ok_or(StakeError::MathematicalOverflowError)?;
+    msg!("User stake balance: {}", user_entry.balance);
+    msg!("User unbonding amount: {}", user_entry.unbonding_amount);
+    msg!("Unbonding ends: {}", user_entry.unbonding_end);
+
+    emit_event!(ctx, UnstakeRequested {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount,
+        balance: user_entry.balance,
+        unbonding_amount: user_entry.unbonding_amount,
+        unbonding_end: user_entry.unbonding_end,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        pending_withdrawal: pool.pending_withdrawal,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RequestUnstakeCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_UNSTAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
+        @ StakeError::InvalidUser
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<SetEmissionsCtx>, emission_rate: u64, emission_start: i64, emission_end: i64) -> Result<()> {
+    if emission_end < emission_start {
+        return err!(StakeError::InvalidEmissionSchedule)
+    }
+
+    // pay out everything streamed under the old schedule before replacing it
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.emission_rate = emission_rate;
+    pool_state.emission_start = emission_start;
+    pool_state.emission_end = emission_end;
+    msg!("Emission rate: {}", pool_state.emission_rate);
+    msg!("Emission start: {}", pool_state.emission_start);
+    msg!("Emission end: {}", pool_state.emission_end);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetEmissionsCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.distributor
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+        constraint = !pool_state.deactivated
+        @ StakeError::PoolDeactivated
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
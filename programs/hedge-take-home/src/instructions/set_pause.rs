@@ -0,0 +1,72 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, utils::*},
+    anchor_spl::token_interface::{TokenAccount, TokenInterface, Mint},
+};
+
+pub fn handler(ctx: Context<SetPauseCtx>, paused: u8) -> Result<()> {
+    if paused & !PAUSE_ALL != 0 {
+        return err!(StakeError::InvalidPauseFlags)
+    }
+
+    // the guardian can only pause, clearing a bit takes the pool authority
+    let pool_state = &mut ctx.accounts.pool_state;
+    let is_admin = ctx.accounts.program_authority.key() == pool_state.authority;
+    if !is_admin && paused & pool_state.paused != pool_state.paused {
+        return err!(StakeError::InvalidProgramAuthority)
+    }
+
+    // settle streaming up to now under the old mask, so pausing stops it from here and unpausing
+    // restarts it from here instead of paying out the paused time
+    accrue_emissions(
+        &mut ctx.accounts.pool_state,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // replaces the whole mask, a cleared bit unpauses its instructions
+    let pool_state = &mut ctx.accounts.pool_state;
+    if pool_state.is_paused(PAUSE_DISTRIBUTE) && paused & PAUSE_DISTRIBUTE == 0 {
+        pool_state.last_emission_update = Clock::get()?.unix_timestamp;
+    }
+    pool_state.paused = paused;
+    msg!("Paused: {:#06b}", pool_state.paused);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPauseCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.authority || program_authority.key() == pool_state.guardian
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), pool_state.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool_state.vault_bump,
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool_state.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool_state.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool_state.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>
+}
\ No newline at end of file
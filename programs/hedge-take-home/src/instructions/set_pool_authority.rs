@@ -0,0 +1,29 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+};
+
+pub fn handler(ctx: Context<SetPoolAuthorityCtx>, new_authority: Pubkey) -> Result<()> {
+
+    // authority only changes once the new key accepts, so a typo can't lock the pool
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.pending_authority = new_authority;
+    msg!("Pending pool authority: {}", pool_state.pending_authority);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPoolAuthorityCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+}
\ No newline at end of file
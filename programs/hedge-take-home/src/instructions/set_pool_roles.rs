@@ -0,0 +1,33 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+};
+
+pub fn handler(ctx: Context<SetPoolRolesCtx>, distributor: Pubkey, burner: Pubkey, guardian: Pubkey) -> Result<()> {
+
+    // each role can only call its own instructions, the pool authority stays admin of everything else
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.distributor = distributor;
+    pool_state.burner = burner;
+    pool_state.guardian = guardian;
+    msg!("Distributor: {}", pool_state.distributor);
+    msg!("Burner: {}", pool_state.burner);
+    msg!("Guardian: {}", pool_state.guardian);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetPoolRolesCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+}
\ No newline at end of file
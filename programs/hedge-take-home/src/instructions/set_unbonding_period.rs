@@ -0,0 +1,32 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+};
+
+pub fn handler(ctx: Context<SetUnbondingPeriodCtx>, unbonding_period: i64) -> Result<()> {
+    if unbonding_period < 0 {
+        return err!(StakeError::InvalidUnbondingPeriod)
+    }
+
+    // only applies to new requests, tokens already unbonding keep their cooldown end
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.unbonding_period = unbonding_period;
+    msg!("Unbonding period: {}", pool_state.unbonding_period);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetUnbondingPeriodCtx<'info> {
+    #[account(
+        constraint = program_authority.key() == pool_state.authority
+        @ StakeError::InvalidProgramAuthority
+    )]
+    pub program_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [pool_state.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump,
+    )]
+    pub pool_state: Account<'info, PoolState>,
+}
\ No newline at end of file
@@ -1,61 +1,77 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*, errors::*},
-    anchor_spl::{token::{TokenAccount, Token, Transfer, transfer}},
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint, TransferChecked, transfer_checked}},
 };
 
-pub fn handler(ctx: Context<StakeCtx>, stake_amount: u64) -> Result<()> {
-    // transfer amount from user token acct to vault
-    transfer(ctx.accounts.transfer_ctx(), stake_amount)?;
+pub fn handler(ctx: Context<StakeCtx>, stake_amount: u64, lock_duration: i64) -> Result<()> {
+    if !(0..=MAX_LOCK_DURATION).contains(&lock_duration) {
+        return err!(StakeError::InvalidLockDuration)
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
+
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // transfer amount from user token acct to vault. Token-2022 mints can take a transfer fee
+    // on the way in, so only what actually reached the vault is staked
+    ctx.accounts.token_vault.reload()?;
+    let pre_vault_amount = ctx.accounts.token_vault.amount;
+    transfer_checked(ctx.accounts.transfer_ctx(), stake_amount, ctx.accounts.token_mint.decimals)?;
+    ctx.accounts.token_vault.reload()?;
+    let stake_amount = ctx.accounts.token_vault.amount.checked_sub(pre_vault_amount).ok_or(StakeError::MathematicalUnderflowError)?;
+    msg!("Amount received by vault: {}", stake_amount);
 
     msg!("Pool initial total: {}", ctx.accounts.pool.amount);
     msg!("Initial user deposits: {}", ctx.accounts.pool.user_deposit_amt);
     msg!("User entry initial balance: {}", ctx.accounts.user_stake_entry.balance);
 
-    if ctx.accounts.user_stake_entry.balance == 0 {
-        // if it's a user's first time staking, this is all that needs to be done
-        ctx.accounts.user_stake_entry.initial_distribution_rate = ctx.accounts.pool.distribution_rate;
-    } 
-    else {
-        msg!("User adding to original stake position");
-
-        // calculate amount of tokens user is owed after rewards/burns are taken into account
-        let out_amount: u128 = calculate_out_amount(&ctx.accounts.pool, &ctx.accounts.user_stake_entry);
-        msg!("Out amount: {}", out_amount);
-
-        // create new staking position with rewards/burn amount included
-        ctx.accounts.pool.user_deposit_amt = ctx.accounts.pool.user_deposit_amt.checked_sub(ctx.accounts.user_stake_entry.balance).unwrap()
-            .checked_add(out_amount as u64).unwrap();
-        msg!("Deposit amt: {}", ctx.accounts.pool.user_deposit_amt);
-
-        ctx.accounts.user_stake_entry.balance = out_amount as u64;
-        msg!("User stake balance: {}", ctx.accounts.user_stake_entry.balance);
-        
-        ctx.accounts.user_stake_entry.initial_distribution_rate = ctx.accounts.pool.distribution_rate;
-    }
-
-    // update pool state amount
+    // an existing position is compounded first so the new tokens start from the current distribution rate,
+    // a lock can be extended but never shortened and the weight follows the time left on it
     let pool = &mut ctx.accounts.pool;
     let user_entry = &mut ctx.accounts.user_stake_entry;
-    pool.amount = pool.amount.checked_add(stake_amount).unwrap();
-    pool.user_deposit_amt = pool.user_deposit_amt.checked_add(stake_amount).unwrap();
+    update_position(pool, user_entry, |pool, position| pool.stake(position, stake_amount, lock_duration, now))?;
     msg!("Current pool total: {}", pool.amount);
     msg!("Amount of tokens deposited by users: {}", pool.user_deposit_amt);
-
-    // update user stake entry
-    user_entry.balance = user_entry.balance.checked_add(stake_amount).unwrap();
     msg!("User entry balance: {}", user_entry.balance);
-    user_entry.last_staked = Clock::get().unwrap().unix_timestamp;
+    msg!("Locked until: {}", user_entry.lock_end);
+    msg!("Lock weight: {}", user_entry.lock_weight_bps);
+    user_entry.last_staked = now;
+
+    emit_event!(ctx, Staked {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount: stake_amount,
+        balance: user_entry.balance,
+        lock_end: user_entry.lock_end,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        timestamp: now
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct StakeCtx <'info> {
     #[account(
         mut,
         seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
-        bump = pool.bump
+        bump = pool.bump,
+        constraint = !pool.deactivated
+        @ StakeError::PoolDeactivated,
+        constraint = !pool.is_paused(PAUSE_STAKE)
+        @ StakeError::Paused
     )]
     pub pool: Account<'info, PoolState>,
     #[account(
@@ -63,16 +79,16 @@ pub struct StakeCtx <'info> {
         seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
         bump = pool.vault_bump
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = user.key() == user_stake_entry.user
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
         @ StakeError::InvalidUser
     )]
     pub user: Signer<'info>,
     #[account(
         mut,
-        seeds = [user.key().as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
         bump = user_stake_entry.bump
     )]
     pub user_stake_entry: Account<'info, StakeEntry>,
@@ -81,17 +97,32 @@ pub struct StakeCtx <'info> {
         constraint = user_token_account.mint == pool.token_mint
         @ StakeError::InvalidMint
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
 }
 
 impl<'info> StakeCtx <'info> {
-    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
         let cpi_program = self.token_program.to_account_info();
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: self.user_token_account.to_account_info(),
+            mint: self.token_mint.to_account_info(),
             to: self.token_vault.to_account_info(),
             authority: self.user.to_account_info()
         };
@@ -0,0 +1,147 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint, TransferChecked, transfer_checked}},
+};
+
+pub fn handler(ctx: Context<StakeForCtx>, stake_amount: u64, lock_duration: i64) -> Result<()> {
+    if !(0..=MAX_LOCK_DURATION).contains(&lock_duration) {
+        return err!(StakeError::InvalidLockDuration)
+    }
+    let now = Clock::get()?.unix_timestamp;
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
+
+    // the funder pays the rent for a beneficiary that has no entry yet
+    if ctx.accounts.beneficiary_stake_entry.user == Pubkey::default() {
+        let bump = *ctx.bumps.get("beneficiary_stake_entry").unwrap();
+        ctx.accounts.beneficiary_stake_entry.open(ctx.accounts.beneficiary.key(), bump, &ctx.accounts.pool);
+
+        let user_entry = &ctx.accounts.beneficiary_stake_entry;
+        emit_event!(ctx, StakeEntryCreated {
+            pool: ctx.accounts.pool.key(),
+            user: user_entry.user,
+            stake_entry: user_entry.key(),
+            distribution_rate: ctx.accounts.pool.distribution_rate,
+            pool_amount: ctx.accounts.pool.amount,
+            user_deposit_amt: ctx.accounts.pool.user_deposit_amt,
+            timestamp: now
+        });
+    }
+
+    // a lock covers the whole position, including anything the beneficiary stakes later, so only the beneficiary can agree to it
+    if lock_duration != 0 && !ctx.accounts.beneficiary.is_signer {
+        return err!(StakeError::LockNotApproved)
+    }
+
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
+    )?;
+
+    // transfer amount from funder token acct to vault, only what actually reached the vault is staked
+    ctx.accounts.token_vault.reload()?;
+    let pre_vault_amount = ctx.accounts.token_vault.amount;
+    transfer_checked(ctx.accounts.transfer_ctx(), stake_amount, ctx.accounts.token_mint.decimals)?;
+    ctx.accounts.token_vault.reload()?;
+    let stake_amount = ctx.accounts.token_vault.amount.checked_sub(pre_vault_amount).ok_or(StakeError::MathematicalUnderflowError)?;
+    msg!("Funder: {}", ctx.accounts.funder.key());
+    msg!("Beneficiary: {}", ctx.accounts.beneficiary.key());
+    msg!("Amount received by vault: {}", stake_amount);
+
+    // the beneficiary owns the position like any other, only it can unstake
+    let pool = &mut ctx.accounts.pool;
+    let user_entry = &mut ctx.accounts.beneficiary_stake_entry;
+    update_position(pool, user_entry, |pool, position| pool.stake(position, stake_amount, lock_duration, now))?;
+    msg!("Current pool total: {}", pool.amount);
+    msg!("Amount of tokens deposited by users: {}", pool.user_deposit_amt);
+    msg!("Beneficiary entry balance: {}", user_entry.balance);
+    msg!("Locked until: {}", user_entry.lock_end);
+    user_entry.last_staked = now;
+
+    emit_event!(ctx, Staked {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount: stake_amount,
+        balance: user_entry.balance,
+        lock_end: user_entry.lock_end,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeForCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.deactivated
+        @ StakeError::PoolDeactivated,
+        constraint = !pool.is_paused(PAUSE_STAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    /// CHECK: This is not dangerous because it is only the owner the stake entry is derived from, it signs to approve a lock
+    pub beneficiary: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        seeds = [beneficiary.key().as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump,
+        payer = funder,
+        space = STAKE_ENTRY_SIZE
+    )]
+    pub beneficiary_stake_entry: Account<'info, StakeEntry>,
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> StakeForCtx <'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, TransferChecked<'info>> {
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_accounts = TransferChecked {
+            from: self.funder_token_account.to_account_info(),
+            mint: self.token_mint.to_account_info(),
+            to: self.token_vault.to_account_info(),
+            authority: self.funder.to_account_info()
+        };
+
+        CpiContext::new(cpi_program, cpi_accounts)
+    }
+}
\ No newline at end of file
@@ -1,69 +1,86 @@
 use {
     anchor_lang::prelude::*,
-    crate::{state::*, errors::*},
-    anchor_spl::{token::{TokenAccount, Token, Mint}},
-    solana_program::{program::invoke_signed},
-    spl_token::instruction::transfer_checked,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
 };
 
-pub fn handler(ctx: Context<UnstakeCtx>) -> Result<()> {
-    // calculate amount of tokens user is owed after rewards/burns are taken into account
-    let out_amount: u128 = calculate_out_amount(&ctx.accounts.pool, &ctx.accounts.user_stake_entry);
-    msg!("Out amount returned: {}", out_amount);
-    msg!("Total staked before withdrawal: {}", ctx.accounts.pool.amount);
-
-    // program signer seeds
-    let auth_bump = ctx.accounts.pool.vault_auth_bump;
-    let auth_seeds = &[VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
-    let signer = &[&auth_seeds[..]];
+pub fn handler(ctx: Context<UnstakeCtx>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    if now < ctx.accounts.user_stake_entry.lock_end {
+        return err!(StakeError::StakeLocked)
+    }
+    // pools with a cooldown only release tokens through request_unstake and withdraw
+    if ctx.accounts.pool.unbonding_period != 0 {
+        return err!(StakeError::UnbondingRequired)
+    }
 
-    // transfer out_amount from stake vault to user
-    let transfer_ix = transfer_checked(
-        &ctx.accounts.token_program.key(),
-        &ctx.accounts.token_vault.key(),
-        &ctx.accounts.token_mint.key(),
-        &ctx.accounts.user_token_account.key(),
-        &ctx.accounts.vault_authority.key(),
-        &[&ctx.accounts.vault_authority.key()],
-        out_amount as u64,
-        6
-    ).unwrap();
+    let pre_distribution_rate = ctx.accounts.pool.distribution_rate;
 
-    invoke_signed(
-        &transfer_ix,
-        &[
-            ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.token_vault.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.user_token_account.to_account_info(),
-            ctx.accounts.vault_authority.to_account_info()
-        ],
-        signer
+    // bring distribution rate up to date with streamed rewards
+    accrue_emissions(
+        &mut ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program
     )?;
 
+    msg!("Amount to withdraw: {}", amount);
+    msg!("Total staked before withdrawal: {}", ctx.accounts.pool.amount);
+
+    // user can only withdraw up to their settled balance after rewards/burns, any remaining
+    // balance stays staked from the current distribution rate. UNSTAKE_ALL takes all of it
     let pool = &mut ctx.accounts.pool;
     let user_entry = &mut ctx.accounts.user_stake_entry;
-
-    // subtract out_amount from pool total
-    pool.amount = pool.amount.checked_sub(out_amount.try_into().unwrap()).unwrap();
-    // subtract amount user had staked originally, not the amount they are receiving after rewards/burn
-    pool.user_deposit_amt = pool.user_deposit_amt.checked_sub(user_entry.balance).unwrap();
+    let amount = update_position(pool, user_entry, |pool, position| {
+        let amount = pool.unstake_amount(position, amount)?;
+        pool.unstake(position, amount, now)?;
+        Ok(amount)
+    })?;
+    msg!("Amount withdrawn: {}", amount);
+    user_entry.last_staked = now;
     msg!("Total staked after withdrawal: {}", pool.amount);
     msg!("Amount deposited by users: {}", pool.user_deposit_amt);
+    msg!("User stake balance: {}", user_entry.balance);
+
+    // transfer amount from stake vault to user
+    transfer_from_vault(
+        &ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program,
+        amount
+    )?;
+    check_solvency(&ctx.accounts.pool, &mut ctx.accounts.token_vault)?;
 
-    // update user stake entry
-    user_entry.balance = 0;
-    user_entry.last_staked = Clock::get().unwrap().unix_timestamp;
+    let pool = &ctx.accounts.pool;
+    let user_entry = &ctx.accounts.user_stake_entry;
+    emit_event!(ctx, Unstaked {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount,
+        balance: user_entry.balance,
+        pre_distribution_rate,
+        post_distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        timestamp: now
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UnstakeCtx <'info> {
     #[account(
         mut,
         seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
-        bump = pool.bump
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_UNSTAKE)
+        @ StakeError::Paused
     )]
     pub pool: Account<'info, PoolState>,
     #[account(
@@ -71,16 +88,16 @@ pub struct UnstakeCtx <'info> {
         seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
         bump = pool.vault_bump
     )]
-    pub token_vault: Account<'info, TokenAccount>,
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        constraint = user.key() == user_stake_entry.user
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
         @ StakeError::InvalidUser
     )]
     pub user: Signer<'info>,
     #[account(
         mut,
-        seeds = [user.key().as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
         bump = user_stake_entry.bump
     )]
     pub user_stake_entry: Account<'info, StakeEntry>,
@@ -89,10 +106,10 @@ pub struct UnstakeCtx <'info> {
         constraint = user_token_account.mint == pool.token_mint
         @ StakeError::InvalidMint
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     /// CHECK: This is not dangerous because we're only using this as a program signer
     #[account(
-        seeds = [VAULT_AUTH_SEED.as_bytes()],
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
         bump = pool.vault_auth_bump
     )]
     pub vault_authority: AccountInfo<'info>,
@@ -101,8 +118,10 @@ pub struct UnstakeCtx <'info> {
         constraint = token_mint.key() == pool.token_mint
         @ StakeError::InvalidMint
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
 
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use {
+    anchor_lang::{prelude::*, Discriminator},
+    crate::{state::*, errors::*, utils::*},
+};
+
+pub fn handler(ctx: Context<UpgradePoolStateCtx>) -> Result<()> {
+    let pool_info = ctx.accounts.pool_state.to_account_info();
+    let legacy_pool: LegacyPoolState = read_legacy_account(&pool_info, PoolState::DISCRIMINATOR, LEGACY_STAKE_POOL_SIZE)?;
+    if legacy_pool.authority != ctx.accounts.program_authority.key() {
+        return err!(StakeError::InvalidProgramAuthority)
+    }
+    if legacy_pool.token_mint != ctx.accounts.token_mint.key() {
+        return err!(StakeError::InvalidMint)
+    }
+
+    // grow the account and write it back in the current layout, with the rate in RATE_PRECISION decimals
+    let pool_state = legacy_pool.upgrade(Clock::get()?.unix_timestamp)?;
+    rewrite_account(
+        &pool_info,
+        &ctx.accounts.program_authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        STAKE_POOL_SIZE,
+        &pool_state
+    )?;
+    msg!("Pool state upgraded: {}", pool_info.key());
+    msg!("Distribution rate: {}", pool_state.distribution_rate);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpgradePoolStateCtx<'info> {
+    #[account(mut)]
+    pub program_authority: Signer<'info>,
+    /// CHECK: still in the launch layout, the handler checks its owner, discriminator and size before reading it
+    #[account(
+        mut,
+        seeds = [token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump
+    )]
+    pub pool_state: UncheckedAccount<'info>,
+    /// CHECK: only used for the pool seeds, the handler matches it against the pool's mint
+    pub token_mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use {
+    anchor_lang::{prelude::*, Discriminator},
+    crate::{state::*, utils::*},
+};
+
+pub fn handler(ctx: Context<UpgradeStakeEntryCtx>) -> Result<()> {
+    let entry_info = ctx.accounts.user_stake_entry.to_account_info();
+    let legacy_entry: LegacyStakeEntry = read_legacy_account(&entry_info, StakeEntry::DISCRIMINATOR, LEGACY_STAKE_ENTRY_SIZE)?;
+
+    // the entry's seeds are only known once it's read, so they're checked here instead of in the context
+    let pool_state = &ctx.accounts.pool_state;
+    let entry_address = Pubkey::create_program_address(
+        &[legacy_entry.user.as_ref(), pool_state.token_mint.as_ref(), STAKE_ENTRY_SEED.as_bytes(), &[legacy_entry.bump]],
+        ctx.program_id
+    ).map_err(|_| ErrorCode::ConstraintSeeds)?;
+    if entry_address != entry_info.key() {
+        return err!(ErrorCode::ConstraintSeeds)
+    }
+
+    // anyone can pay for the upgrade, the entry keeps its user, balance and converted rate
+    let user_stake_entry = legacy_entry.upgrade(pool_state)?;
+    rewrite_account(
+        &entry_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        STAKE_ENTRY_SIZE,
+        &user_stake_entry
+    )?;
+    msg!("Stake entry upgraded: {}", entry_info.key());
+    msg!("User staked amount: {}", user_stake_entry.balance);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpgradeStakeEntryCtx<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        seeds = [pool_state.token_mint.as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool_state.bump
+    )]
+    pub pool_state: Account<'info, PoolState>,
+    /// CHECK: still in the launch layout, the handler checks its owner, discriminator, size and seeds before reading it
+    #[account(mut)]
+    pub user_stake_entry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>
+}
\ No newline at end of file
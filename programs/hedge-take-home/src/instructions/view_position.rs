@@ -0,0 +1,62 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*},
+};
+
+pub fn handler(ctx: Context<ViewPositionCtx>) -> Result<PositionView> {
+    let now = Clock::get()?.unix_timestamp;
+
+    // quote against the pool as unstake would see it, streamed rewards are distributed on a copy and nothing is written.
+    // Off-chain quotes go through the same staking-math path
+    let engine = ctx.accounts.pool.layout().engine_at(now).map_err(StakeError::from)?;
+
+    // an entry left over from a closed pool at the same address is empty in this one
+    let user_entry = &ctx.accounts.user_stake_entry;
+    let mut position = if user_entry.is_current(&ctx.accounts.pool) {
+        user_entry.position()
+    } else {
+        engine.open_position()
+    };
+    let principal = position.balance;
+    let settled_balance = engine.settled_balance(&position).map_err(StakeError::from)?;
+
+    // growth of the position since its last checkpoint, 1.0 for an empty position
+    let effective_rate = if principal == 0 {
+        RATE_PRECISION
+    } else {
+        (settled_balance as u128).checked_mul(RATE_PRECISION).ok_or(StakeError::MathematicalOverflowError)? / principal as u128
+    };
+
+    // reward mint earnings the position would be paid by claim_rewards
+    engine.settle_rewards(&mut position).map_err(StakeError::from)?;
+
+    let view = PositionView {
+        principal,
+        settled_balance,
+        accrued_rewards: settled_balance.saturating_sub(principal),
+        burned_amount: principal.saturating_sub(settled_balance),
+        effective_rate,
+        rewards_owed: position.rewards_owed,
+        unbonding_amount: user_entry.unbonding_amount,
+        unbonding_end: user_entry.unbonding_end
+    };
+    msg!("Settled balance: {}", view.settled_balance);
+    msg!("Effective rate: {}", view.effective_rate);
+
+    Ok(view)
+}
+
+#[derive(Accounts)]
+pub struct ViewPositionCtx<'info> {
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, PoolState>,
+    // read only and permissionless, anyone can quote any position
+    #[account(
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>
+}
\ No newline at end of file
@@ -0,0 +1,100 @@
+use {
+    anchor_lang::prelude::*,
+    crate::{state::*, errors::*, events::*, utils::*},
+    anchor_spl::{token_interface::{TokenAccount, TokenInterface, Mint}},
+};
+
+pub fn handler(ctx: Context<WithdrawCtx>) -> Result<()> {
+    let amount = ctx.accounts.user_stake_entry.unbonding_amount;
+    if amount == 0 {
+        return err!(StakeError::NothingToWithdraw)
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if now < ctx.accounts.user_stake_entry.unbonding_end {
+        return err!(StakeError::UnbondingNotComplete)
+    }
+    msg!("Amount to withdraw: {}", amount);
+
+    // transfer unbonded tokens from stake vault to user
+    transfer_from_vault(
+        &ctx.accounts.pool,
+        &ctx.accounts.token_vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.user_token_account.to_account_info(),
+        &ctx.accounts.vault_authority,
+        &ctx.accounts.token_program,
+        amount
+    )?;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.pending_withdrawal = pool.pending_withdrawal.checked_sub(amount).ok_or(StakeError::MathematicalUnderflowError)?;
+    msg!("Total pending withdrawal: {}", pool.pending_withdrawal);
+
+    let user_entry = &mut ctx.accounts.user_stake_entry;
+    user_entry.unbonding_amount = 0;
+    user_entry.unbonding_end = 0;
+
+    emit_event!(ctx, Withdrawn {
+        pool: pool.key(),
+        user: user_entry.user,
+        amount,
+        distribution_rate: pool.distribution_rate,
+        pool_amount: pool.amount,
+        user_deposit_amt: pool.user_deposit_amt,
+        pending_withdrawal: pool.pending_withdrawal,
+        timestamp: now
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawCtx <'info> {
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), STAKE_POOL_STATE_SEED.as_bytes()],
+        bump = pool.bump,
+        constraint = !pool.is_paused(PAUSE_UNSTAKE)
+        @ StakeError::Paused
+    )]
+    pub pool: Account<'info, PoolState>,
+    #[account(
+        mut,
+        seeds = [pool.token_mint.key().as_ref(), pool.vault_authority.key().as_ref(), VAULT_SEED.as_bytes()],
+        bump = pool.vault_bump
+    )]
+    pub token_vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        constraint = user_stake_entry.is_owned_by(&user.key(), position_token_account.as_deref())
+        @ StakeError::InvalidUser
+    )]
+    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [user_stake_entry.user.as_ref(), pool.token_mint.key().as_ref(), STAKE_ENTRY_SEED.as_bytes()],
+        bump = user_stake_entry.bump
+    )]
+    pub user_stake_entry: Account<'info, StakeEntry>,
+    #[account(
+        mut,
+        constraint = user_token_account.mint == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: This is not dangerous because we're only using this as a program signer
+    #[account(
+        seeds = [pool.token_mint.key().as_ref(), VAULT_AUTH_SEED.as_bytes()],
+        bump = pool.vault_auth_bump
+    )]
+    pub vault_authority: AccountInfo<'info>,
+    #[account(
+        constraint = token_mint.key() == pool.token_mint
+        @ StakeError::InvalidMint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    /// Only for entries opened with init_position_entry, the user's token account holding the position token
+    pub position_token_account: Option<InterfaceAccount<'info, TokenAccount>>
+}
\ No newline at end of file
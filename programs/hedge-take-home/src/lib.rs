@@ -1,9 +1,10 @@
 pub mod errors;
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
 
-use {anchor_lang::prelude::*, instructions::*};
+use {anchor_lang::prelude::*, instructions::*, state::PositionView};
 
 declare_id!("2wMP4GLFkKV3eZnr17PnB4JStRzUN4oet4xmvmgHWq9t");
 
@@ -19,12 +20,20 @@ pub mod hedge_take_home {
         init_stake_entry::handler(ctx)
     }
 
-    pub fn stake(ctx: Context<StakeCtx>, amount: u64) -> Result<()> {
-        stake::handler(ctx, amount)
+    pub fn init_position_entry(ctx: Context<InitPositionEntryCtx>) -> Result<()> {
+        init_position_entry::handler(ctx)
     }
 
-    pub fn unstake(ctx: Context<UnstakeCtx>) -> Result<()> {
-        unstake::handler(ctx)
+    pub fn stake(ctx: Context<StakeCtx>, amount: u64, lock_duration: i64) -> Result<()> {
+        stake::handler(ctx, amount, lock_duration)
+    }
+
+    pub fn stake_for(ctx: Context<StakeForCtx>, amount: u64, lock_duration: i64) -> Result<()> {
+        stake_for::handler(ctx, amount, lock_duration)
+    }
+
+    pub fn unstake(ctx: Context<UnstakeCtx>, amount: u64) -> Result<()> {
+        unstake::handler(ctx, amount)
     }
 
     pub fn distribute(ctx: Context<DistributeCtx>, amount: u64) -> Result<()> {
@@ -34,4 +43,100 @@ pub mod hedge_take_home {
     pub fn burn(ctx: Context<BurnCtx>, amount: u64) -> Result<()> {
         burn::handler(ctx, amount)
     }
+
+    pub fn migrate_vault_authority(ctx: Context<MigrateVaultAuthorityCtx>) -> Result<()> {
+        migrate_vault_authority::handler(ctx)
+    }
+
+    pub fn set_pool_authority(ctx: Context<SetPoolAuthorityCtx>, new_authority: Pubkey) -> Result<()> {
+        set_pool_authority::handler(ctx, new_authority)
+    }
+
+    pub fn accept_pool_authority(ctx: Context<AcceptPoolAuthorityCtx>) -> Result<()> {
+        accept_pool_authority::handler(ctx)
+    }
+
+    pub fn set_emissions(ctx: Context<SetEmissionsCtx>, emission_rate: u64, emission_start: i64, emission_end: i64) -> Result<()> {
+        set_emissions::handler(ctx, emission_rate, emission_start, emission_end)
+    }
+
+    pub fn crank_emissions(ctx: Context<CrankEmissionsCtx>) -> Result<()> {
+        crank_emissions::handler(ctx)
+    }
+
+    pub fn add_reward_mint(ctx: Context<AddRewardMintCtx>) -> Result<()> {
+        add_reward_mint::handler(ctx)
+    }
+
+    pub fn distribute_reward(ctx: Context<DistributeRewardCtx>, amount: u64) -> Result<()> {
+        distribute_reward::handler(ctx, amount)
+    }
+
+    pub fn claim_rewards<'info>(ctx: Context<'_, '_, '_, 'info, ClaimRewardsCtx<'info>>) -> Result<()> {
+        claim_rewards::handler(ctx)
+    }
+
+    pub fn claim(ctx: Context<ClaimCtx>) -> Result<()> {
+        claim::handler(ctx)
+    }
+
+    pub fn compound(ctx: Context<CompoundCtx>) -> Result<()> {
+        compound::handler(ctx)
+    }
+
+    pub fn set_unbonding_period(ctx: Context<SetUnbondingPeriodCtx>, unbonding_period: i64) -> Result<()> {
+        set_unbonding_period::handler(ctx, unbonding_period)
+    }
+
+    pub fn request_unstake(ctx: Context<RequestUnstakeCtx>, amount: u64) -> Result<()> {
+        request_unstake::handler(ctx, amount)
+    }
+
+    pub fn withdraw(ctx: Context<WithdrawCtx>) -> Result<()> {
+        withdraw::handler(ctx)
+    }
+
+    pub fn view_position(ctx: Context<ViewPositionCtx>) -> Result<PositionView> {
+        view_position::handler(ctx)
+    }
+
+    pub fn close_stake_entry(ctx: Context<CloseStakeEntryCtx>) -> Result<()> {
+        close_stake_entry::handler(ctx)
+    }
+
+    pub fn deactivate_pool(ctx: Context<DeactivatePoolCtx>) -> Result<()> {
+        deactivate_pool::handler(ctx)
+    }
+
+    pub fn close_pool<'info>(ctx: Context<'_, '_, '_, 'info, ClosePoolCtx<'info>>) -> Result<()> {
+        close_pool::handler(ctx)
+    }
+
+    pub fn set_pause(ctx: Context<SetPauseCtx>, paused: u8) -> Result<()> {
+        set_pause::handler(ctx, paused)
+    }
+
+    pub fn set_pool_roles(ctx: Context<SetPoolRolesCtx>, distributor: Pubkey, burner: Pubkey, guardian: Pubkey) -> Result<()> {
+        set_pool_roles::handler(ctx, distributor, burner, guardian)
+    }
+
+    pub fn reconcile(ctx: Context<ReconcileCtx>, correct: bool) -> Result<()> {
+        reconcile::handler(ctx, correct)
+    }
+
+    pub fn check_invariants(ctx: Context<CheckInvariantsCtx>) -> Result<()> {
+        check_invariants::handler(ctx)
+    }
+
+    pub fn upgrade_pool_state(ctx: Context<UpgradePoolStateCtx>) -> Result<()> {
+        upgrade_pool_state::handler(ctx)
+    }
+
+    pub fn upgrade_stake_entry(ctx: Context<UpgradeStakeEntryCtx>) -> Result<()> {
+        upgrade_stake_entry::handler(ctx)
+    }
+
+    pub fn poke_position(ctx: Context<PokePositionCtx>) -> Result<()> {
+        poke_position::handler(ctx)
+    }
 }
\ No newline at end of file
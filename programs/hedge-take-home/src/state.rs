@@ -1,21 +1,42 @@
 use {
     anchor_lang::prelude::*,
+    crate::errors::*,
+    anchor_spl::token_interface::TokenAccount,
     solana_program::{pubkey, pubkey::Pubkey},
 };
 
+pub use staking_math::{
+    MathResult, Pool, PoolStateAccount, Position, RewardInfoAccount, StakeEntryAccount, BASE_WEIGHT_BPS, MAX_LOCK_DURATION, MAX_WEIGHT_BPS, RATE_PRECISION, UNSTAKE_ALL,
+    PAUSE_STAKE, PAUSE_UNSTAKE, PAUSE_DISTRIBUTE, PAUSE_BURN, PAUSE_ALL
+};
+
 pub const STAKE_POOL_STATE_SEED: &str = "state";
-pub const STAKE_POOL_SIZE: usize = 8 + 32 + 32 + 1 + 8 + 32 + 8 + 1 + 1 + 32 + 16 + 8;
+pub const STAKE_POOL_SIZE: usize = 8 + 32 + 32 + 1 + 8 + 32 + 8 + 1 + 1 + 32 + 16 + 8 + 32 + 8 + 8 + 8 + 8 + MAX_REWARD_MINTS * REWARD_INFO_SIZE + 16 + 16 + 8 + 8 + 8 + 8 + 16 + 8 + 8 + 16 + 1 + 1 + 32 + 32 + 32 + MAX_REWARD_MINTS * 8 + 8 + POOL_RESERVED_SIZE;
 
 pub const VAULT_SEED: &str = "vault";
 pub const VAULT_AUTH_SEED: &str = "vault_authority";
 
 pub const STAKE_ENTRY_SEED: &str = "stake_entry";
-pub const STAKE_ENTRY_SIZE: usize = 8 + 32 + 1 + 8 + 8 + 16;
+pub const STAKE_ENTRY_SIZE: usize = 8 + 32 + 1 + 8 + 8 + 16 + MAX_REWARD_MINTS * (16 + 8) + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 8 + 32 + 8 + ENTRY_RESERVED_SIZE;
+
+pub const REWARD_VAULT_SEED: &str = "reward_vault";
+pub const MAX_REWARD_MINTS: usize = 4;
+pub const REWARD_INFO_SIZE: usize = 32 + 32 + 1 + 16 + 16 + 16;
+
+// zeroed space at the end of each account, new fields are carved out of it so existing accounts keep their size
+pub const POOL_RESERVED_SIZE: usize = 88;
+pub const ENTRY_RESERVED_SIZE: usize = 56;
+
+// sizes accounts were created with before the upgrade instructions, see LegacyPoolState and LegacyStakeEntry
+pub const LEGACY_STAKE_POOL_SIZE: usize = 8 + 32 + 32 + 1 + 8 + 32 + 8 + 1 + 1 + 32 + 16 + 8;
+pub const LEGACY_STAKE_ENTRY_SIZE: usize = 8 + 32 + 1 + 8 + 8 + 16;
+
+// launch-layout rates had RATE_MULT decimals, with 1 standing in for 1.0 until the first distribution
+pub const LEGACY_RATE_MULT: u128 = 100_000_000_000;
 
 pub static PROGRAM_AUTHORITY: Pubkey = pubkey!("9MNHTJJ1wd6uQrZfXk46T24qcWNZYpYfwZKk6zho4poV");
 
 pub const MULT: u128 = 10_000_000_000;
-pub const RATE_MULT: u128 = 100_000_000_000;
 
 
 #[account]
@@ -30,11 +51,102 @@ pub struct PoolState {
     pub vault_auth_bump: u8,
     pub vault_authority: Pubkey,
     pub distribution_rate: u128,
-    pub user_deposit_amt: u64
+    pub user_deposit_amt: u64,
+    pub pending_authority: Pubkey,
+    pub emission_rate: u64,
+    pub emission_start: i64,
+    pub emission_end: i64,
+    pub last_emission_update: i64,
+    pub reward_infos: [RewardInfo; MAX_REWARD_MINTS],
+    pub boost_rate: u128,
+    pub total_boost_weight: u128,
+    pub boost_reserve: u64,
+    pub unbonding_period: i64,
+    pub pending_withdrawal: u64,
+    pub rate_scale: u64,
+    pub prev_scale_boost_rate: u128,
+    pub epoch: u64,
+    pub epoch_end_rate_scale: u64,
+    pub epoch_end_boost_rate: u128,
+    pub deactivated: bool,
+    pub paused: u8,
+    pub distributor: Pubkey,
+    pub burner: Pubkey,
+    pub guardian: Pubkey,
+    /// Reward mint earnings settled into entries and not claimed yet, per reward slot
+    pub rewards_owed_total: [u64; MAX_REWARD_MINTS],
+    /// The slot init_pool ran in, a pool re-initialized after close_pool gets a new one. Zero for upgraded pools
+    pub generation: u64,
+    pub reserved: [u8; POOL_RESERVED_SIZE]
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RewardInfo {
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub vault_bump: u8,
+    pub reward_rate: u128,
+    pub prev_scale_reward_rate: u128,
+    pub epoch_end_reward_rate: u128
+}
+
+/// Returned by view_position through set_return_data, what the entry would be paid if it unstaked now
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct PositionView {
+    pub principal: u64,
+    pub settled_balance: u64,
+    pub accrued_rewards: u64,
+    pub burned_amount: u64,
+    /// settled_balance / principal, with RATE_PRECISION decimals
+    pub effective_rate: u128,
+    pub rewards_owed: [u64; MAX_REWARD_MINTS],
+    pub unbonding_amount: u64,
+    pub unbonding_end: i64
 }
 
 #[account]
 pub struct StakeEntry {
+    pub user: Pubkey,
+    pub bump: u8,
+    pub balance: u64,
+    pub last_staked: i64,
+    pub initial_distribution_rate: u128,
+    pub reward_checkpoints: [u128; MAX_REWARD_MINTS],
+    pub rewards_owed: [u64; MAX_REWARD_MINTS],
+    pub lock_end: i64,
+    pub lock_weight_bps: u64,
+    pub boost_weight: u64,
+    pub boost_checkpoint: u128,
+    pub unbonding_amount: u64,
+    pub unbonding_end: i64,
+    pub initial_rate_scale: u64,
+    pub initial_epoch: u64,
+    /// Set for entries opened with init_position_entry, `user` is then the position mint as well
+    pub position_mint: Pubkey,
+    /// The generation of the pool the entry was opened in, entries of an earlier pool at the same address are stale
+    pub pool_generation: u64,
+    pub reserved: [u8; ENTRY_RESERVED_SIZE]
+}
+
+/// PoolState as created before the account grew, upgrade_pool_state reads it and rewrites the account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LegacyPoolState {
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub amount: u64,
+    pub token_vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub initialized_at: i64,
+    pub vault_bump: u8,
+    pub vault_auth_bump: u8,
+    pub vault_authority: Pubkey,
+    pub distribution_rate: u128,
+    pub user_deposit_amt: u64
+}
+
+/// StakeEntry as created before the account grew, upgrade_stake_entry reads it and rewrites the account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LegacyStakeEntry {
     pub user: Pubkey,
     pub bump: u8,
     pub balance: u64,
@@ -42,26 +154,396 @@ pub struct StakeEntry {
     pub initial_distribution_rate: u128
 }
 
-pub fn calculate_out_amount(pool_state: &PoolState, user_stake_entry: &StakeEntry) -> u128 {
-    // using a single distribution rate
-    let distribution_rate: u128;
+impl PoolState {
+    pub fn is_paused(&self, flag: u8) -> bool {
+        self.paused & flag != 0
+    }
+
+    /// Tokens the vault has to hold: staked, boost earnings not yet checkpointed and tokens waiting to be withdrawn
+    pub fn expected_vault_amount(&self) -> Result<u64> {
+        let expected_amount = self.amount
+            .checked_add(self.boost_reserve).ok_or(StakeError::MathematicalOverflowError)?
+            .checked_add(self.pending_withdrawal).ok_or(StakeError::MathematicalOverflowError)?;
+
+        Ok(expected_amount)
+    }
+
+    /// The pool as staking-math lays the account out
+    pub fn layout(&self) -> PoolStateAccount<MAX_REWARD_MINTS> {
+        let mut reward_infos = [RewardInfoAccount::default(); MAX_REWARD_MINTS];
+        for (layout, reward_info) in reward_infos.iter_mut().zip(self.reward_infos.iter()) {
+            *layout = RewardInfoAccount {
+                mint: reward_info.mint.to_bytes(),
+                vault: reward_info.vault.to_bytes(),
+                vault_bump: reward_info.vault_bump,
+                reward_rate: reward_info.reward_rate,
+                prev_scale_reward_rate: reward_info.prev_scale_reward_rate,
+                epoch_end_reward_rate: reward_info.epoch_end_reward_rate
+            };
+        }
+
+        PoolStateAccount {
+            authority: self.authority.to_bytes(),
+            bump: self.bump,
+            amount: self.amount,
+            token_vault: self.token_vault.to_bytes(),
+            token_mint: self.token_mint.to_bytes(),
+            initialized_at: self.initialized_at,
+            vault_bump: self.vault_bump,
+            vault_auth_bump: self.vault_auth_bump,
+            vault_authority: self.vault_authority.to_bytes(),
+            distribution_rate: self.distribution_rate,
+            user_deposit_amt: self.user_deposit_amt,
+            pending_authority: self.pending_authority.to_bytes(),
+            emission_rate: self.emission_rate,
+            emission_start: self.emission_start,
+            emission_end: self.emission_end,
+            last_emission_update: self.last_emission_update,
+            reward_infos,
+            boost_rate: self.boost_rate,
+            total_boost_weight: self.total_boost_weight,
+            boost_reserve: self.boost_reserve,
+            unbonding_period: self.unbonding_period,
+            pending_withdrawal: self.pending_withdrawal,
+            rate_scale: self.rate_scale,
+            prev_scale_boost_rate: self.prev_scale_boost_rate,
+            epoch: self.epoch,
+            epoch_end_rate_scale: self.epoch_end_rate_scale,
+            epoch_end_boost_rate: self.epoch_end_boost_rate,
+            deactivated: self.deactivated,
+            paused: self.paused,
+            distributor: self.distributor.to_bytes(),
+            burner: self.burner.to_bytes(),
+            guardian: self.guardian.to_bytes(),
+            rewards_owed_total: self.rewards_owed_total,
+            generation: self.generation
+        }
+    }
+
+    /// Copies every field of a staking-math layout back into the account
+    pub fn apply_layout(&mut self, layout: &PoolStateAccount<MAX_REWARD_MINTS>) {
+        self.authority = Pubkey::new_from_array(layout.authority);
+        self.bump = layout.bump;
+        self.amount = layout.amount;
+        self.token_vault = Pubkey::new_from_array(layout.token_vault);
+        self.token_mint = Pubkey::new_from_array(layout.token_mint);
+        self.initialized_at = layout.initialized_at;
+        self.vault_bump = layout.vault_bump;
+        self.vault_auth_bump = layout.vault_auth_bump;
+        self.vault_authority = Pubkey::new_from_array(layout.vault_authority);
+        self.distribution_rate = layout.distribution_rate;
+        self.user_deposit_amt = layout.user_deposit_amt;
+        self.pending_authority = Pubkey::new_from_array(layout.pending_authority);
+        self.emission_rate = layout.emission_rate;
+        self.emission_start = layout.emission_start;
+        self.emission_end = layout.emission_end;
+        self.last_emission_update = layout.last_emission_update;
+        for (reward_info, layout) in self.reward_infos.iter_mut().zip(layout.reward_infos.iter()) {
+            *reward_info = RewardInfo {
+                mint: Pubkey::new_from_array(layout.mint),
+                vault: Pubkey::new_from_array(layout.vault),
+                vault_bump: layout.vault_bump,
+                reward_rate: layout.reward_rate,
+                prev_scale_reward_rate: layout.prev_scale_reward_rate,
+                epoch_end_reward_rate: layout.epoch_end_reward_rate
+            };
+        }
+        self.boost_rate = layout.boost_rate;
+        self.total_boost_weight = layout.total_boost_weight;
+        self.boost_reserve = layout.boost_reserve;
+        self.unbonding_period = layout.unbonding_period;
+        self.pending_withdrawal = layout.pending_withdrawal;
+        self.rate_scale = layout.rate_scale;
+        self.prev_scale_boost_rate = layout.prev_scale_boost_rate;
+        self.epoch = layout.epoch;
+        self.epoch_end_rate_scale = layout.epoch_end_rate_scale;
+        self.epoch_end_boost_rate = layout.epoch_end_boost_rate;
+        self.deactivated = layout.deactivated;
+        self.paused = layout.paused;
+        self.distributor = Pubkey::new_from_array(layout.distributor);
+        self.burner = Pubkey::new_from_array(layout.burner);
+        self.guardian = Pubkey::new_from_array(layout.guardian);
+        self.rewards_owed_total = layout.rewards_owed_total;
+        self.generation = layout.generation;
+    }
+
+    /// The pool's accounting state as the staking-math engine sees it
+    pub fn engine(&self) -> Pool<MAX_REWARD_MINTS> {
+        self.layout().engine()
+    }
+
+    pub fn save_engine(&mut self, engine: &Pool<MAX_REWARD_MINTS>) {
+        let mut layout = self.layout();
+        layout.save_engine(engine);
+        self.apply_layout(&layout);
+    }
+}
+
+impl StakeEntry {
+    /// Sets up a new entry for `user`, starting from the pool's current rates
+    pub fn open(&mut self, user: Pubkey, bump: u8, pool_state: &PoolState) {
+        self.user = user;
+        self.bump = bump;
+        self.position_mint = Pubkey::default();
+        self.restart(pool_state);
+    }
+
+    /// Whether the entry was opened in this pool and not in an earlier one closed at the same address
+    pub fn is_current(&self, pool_state: &PoolState) -> bool {
+        self.pool_generation == pool_state.generation
+    }
+
+    /// Starts the entry over from the pool's current rates, keeping its user and position mint. close_pool only
+    /// goes through once every entry is paid out, so a stale entry has nothing left to settle
+    pub fn restart(&mut self, pool_state: &PoolState) {
+        self.save_position(&pool_state.engine().open_position());
+        self.unbonding_amount = 0;
+        self.unbonding_end = 0;
+        self.pool_generation = pool_state.generation;
+    }
+
+    /// Whether `user` can act on the entry, either as its user or by holding its position token
+    pub fn is_owned_by(&self, user: &Pubkey, position_token_account: Option<&TokenAccount>) -> bool {
+        if self.position_mint == Pubkey::default() {
+            return *user == self.user
+        }
+
+        position_token_account.is_some_and(|token_account| {
+            token_account.mint == self.position_mint && token_account.owner == *user && token_account.amount == 1
+        })
+    }
+
+    /// The entry as staking-math lays the account out
+    pub fn layout(&self) -> StakeEntryAccount<MAX_REWARD_MINTS> {
+        StakeEntryAccount {
+            user: self.user.to_bytes(),
+            bump: self.bump,
+            balance: self.balance,
+            last_staked: self.last_staked,
+            initial_distribution_rate: self.initial_distribution_rate,
+            reward_checkpoints: self.reward_checkpoints,
+            rewards_owed: self.rewards_owed,
+            lock_end: self.lock_end,
+            lock_weight_bps: self.lock_weight_bps,
+            boost_weight: self.boost_weight,
+            boost_checkpoint: self.boost_checkpoint,
+            unbonding_amount: self.unbonding_amount,
+            unbonding_end: self.unbonding_end,
+            initial_rate_scale: self.initial_rate_scale,
+            initial_epoch: self.initial_epoch,
+            position_mint: self.position_mint.to_bytes(),
+            pool_generation: self.pool_generation
+        }
+    }
+
+    /// Copies every field of a staking-math layout back into the account
+    pub fn apply_layout(&mut self, layout: &StakeEntryAccount<MAX_REWARD_MINTS>) {
+        self.user = Pubkey::new_from_array(layout.user);
+        self.bump = layout.bump;
+        self.balance = layout.balance;
+        self.last_staked = layout.last_staked;
+        self.initial_distribution_rate = layout.initial_distribution_rate;
+        self.reward_checkpoints = layout.reward_checkpoints;
+        self.rewards_owed = layout.rewards_owed;
+        self.lock_end = layout.lock_end;
+        self.lock_weight_bps = layout.lock_weight_bps;
+        self.boost_weight = layout.boost_weight;
+        self.boost_checkpoint = layout.boost_checkpoint;
+        self.unbonding_amount = layout.unbonding_amount;
+        self.unbonding_end = layout.unbonding_end;
+        self.initial_rate_scale = layout.initial_rate_scale;
+        self.initial_epoch = layout.initial_epoch;
+        self.position_mint = Pubkey::new_from_array(layout.position_mint);
+        self.pool_generation = layout.pool_generation;
+    }
+
+    /// The entry's accounting state as the staking-math engine sees it
+    pub fn position(&self) -> Position<MAX_REWARD_MINTS> {
+        self.layout().position()
+    }
+
+    pub fn save_position(&mut self, position: &Position<MAX_REWARD_MINTS>) {
+        let mut layout = self.layout();
+        layout.save_position(position);
+        self.apply_layout(&layout);
+    }
+}
+
+impl LegacyPoolState {
+    /// The pool in the current layout, every field added since launch starts the way init_pool sets it
+    pub fn upgrade(&self, now: i64) -> Result<PoolState> {
+        Ok(PoolState {
+            authority: self.authority,
+            bump: self.bump,
+            amount: self.amount,
+            token_vault: self.token_vault,
+            token_mint: self.token_mint,
+            initialized_at: self.initialized_at,
+            vault_bump: self.vault_bump,
+            vault_auth_bump: self.vault_auth_bump,
+            vault_authority: self.vault_authority,
+            distribution_rate: convert_legacy_rate(self.distribution_rate)?,
+            user_deposit_amt: self.user_deposit_amt,
+            pending_authority: Pubkey::default(),
+            emission_rate: 0,
+            emission_start: 0,
+            emission_end: 0,
+            last_emission_update: now,
+            reward_infos: [RewardInfo::default(); MAX_REWARD_MINTS],
+            boost_rate: 0,
+            total_boost_weight: 0,
+            boost_reserve: 0,
+            unbonding_period: 0,
+            pending_withdrawal: 0,
+            rate_scale: 0,
+            prev_scale_boost_rate: 0,
+            epoch: 0,
+            epoch_end_rate_scale: 0,
+            epoch_end_boost_rate: 0,
+            deactivated: false,
+            paused: 0,
+            distributor: self.authority,
+            burner: self.authority,
+            guardian: self.authority,
+            rewards_owed_total: [0; MAX_REWARD_MINTS],
+            generation: 0,
+            reserved: [0; POOL_RESERVED_SIZE]
+        })
+    }
+}
+
+impl LegacyStakeEntry {
+    /// The entry in the current layout, unlocked and without boost or reward checkpoints
+    pub fn upgrade(&self, pool_state: &PoolState) -> Result<StakeEntry> {
+        let mut position = pool_state.engine().open_position();
+        // an emptied entry has nothing to settle, it just starts over from the pool's current rate
+        if self.balance != 0 {
+            position.balance = self.balance;
+            position.initial_distribution_rate = convert_legacy_rate(self.initial_distribution_rate)?;
+            position.initial_rate_scale = 0;
+            position.initial_epoch = 0;
+            position.reward_checkpoints = [0; MAX_REWARD_MINTS];
+            position.boost_checkpoint = 0;
+        }
+
+        let mut user_stake_entry = StakeEntry {
+            user: self.user,
+            bump: self.bump,
+            balance: 0,
+            last_staked: self.last_staked,
+            initial_distribution_rate: 0,
+            reward_checkpoints: [0; MAX_REWARD_MINTS],
+            rewards_owed: [0; MAX_REWARD_MINTS],
+            lock_end: 0,
+            lock_weight_bps: 0,
+            boost_weight: 0,
+            boost_checkpoint: 0,
+            unbonding_amount: 0,
+            unbonding_end: 0,
+            initial_rate_scale: 0,
+            initial_epoch: 0,
+            position_mint: Pubkey::default(),
+            pool_generation: pool_state.generation,
+            reserved: [0; ENTRY_RESERVED_SIZE]
+        };
+        user_stake_entry.save_position(&position);
+
+        Ok(user_stake_entry)
+    }
+}
+
+/// A launch-layout rate in RATE_PRECISION decimals
+pub fn convert_legacy_rate(rate: u128) -> Result<u128> {
+    if rate == 1 {
+        return Ok(RATE_PRECISION)
+    }
+
+    rate.checked_mul(RATE_PRECISION / LEGACY_RATE_MULT).ok_or_else(|| error!(StakeError::MathematicalOverflowError))
+}
+
+/// Runs a staking-math operation on the pool, the pool is only written back if it succeeds
+pub fn update_pool<T>(
+    pool_state: &mut PoolState,
+    op: impl FnOnce(&mut Pool<MAX_REWARD_MINTS>) -> MathResult<T>
+) -> Result<T> {
+    let mut engine = pool_state.engine();
+    let result = op(&mut engine).map_err(StakeError::from)?;
+    pool_state.save_engine(&engine);
+
+    Ok(result)
+}
+
+/// Runs a staking-math operation on the pool and a stake entry, both are only written back if it succeeds
+pub fn update_position<T>(
+    pool_state: &mut PoolState,
+    user_stake_entry: &mut StakeEntry,
+    op: impl FnOnce(&mut Pool<MAX_REWARD_MINTS>, &mut Position<MAX_REWARD_MINTS>) -> MathResult<T>
+) -> Result<T> {
+    // an entry left over from a closed pool at the same address starts over in this one
+    if !user_stake_entry.is_current(pool_state) {
+        user_stake_entry.restart(pool_state);
+    }
+
+    let mut engine = pool_state.engine();
+    let mut position = user_stake_entry.position();
+    let result = op(&mut engine, &mut position).map_err(StakeError::from)?;
+    pool_state.save_engine(&engine);
+    track_rewards_owed(pool_state, &user_stake_entry.rewards_owed, &position.rewards_owed)?;
+    user_stake_entry.save_position(&position);
+
+    Ok(result)
+}
+
+/// Moves the pool's unclaimed reward totals along with a change to an entry's rewards_owed
+pub fn track_rewards_owed(pool_state: &mut PoolState, pre_owed: &[u64; MAX_REWARD_MINTS], post_owed: &[u64; MAX_REWARD_MINTS]) -> Result<()> {
+    for ((total, pre), post) in pool_state.rewards_owed_total.iter_mut().zip(pre_owed.iter()).zip(post_owed.iter()) {
+        *total = total
+            .checked_sub(*pre).ok_or(StakeError::MathematicalUnderflowError)?
+            .checked_add(*post).ok_or(StakeError::MathematicalOverflowError)?;
+    }
+
+    Ok(())
+}
+
+pub fn apply_distribution(pool_state: &mut PoolState, amount: u64) -> Result<()> {
+    let pre_boost_reserve = pool_state.boost_reserve;
+    update_pool(pool_state, |pool| pool.distribute(amount))?;
+
+    msg!("Rewards to distribute: {}", amount);
+    msg!("Boosted rewards: {}", pool_state.boost_reserve - pre_boost_reserve);
+    msg!("Total staked after distribution: {}", pool_state.amount);
+    msg!("User deposits: {}", pool_state.user_deposit_amt);
+    msg!("Distribution rate: {}", pool_state.distribution_rate);
+
+    Ok(())
+}
+
+pub fn apply_burn(pool_state: &mut PoolState, amount: u64) -> Result<()> {
+    // only staked tokens can be burned, never the boost reserve or tokens waiting to be withdrawn
+    let pre_epoch = pool_state.epoch;
+    update_pool(pool_state, |pool| pool.burn(amount))?;
 
-    if user_stake_entry.initial_distribution_rate == 1 {
-        distribution_rate = pool_state.distribution_rate;
-        msg!("initial rate == 1");
-        msg!("Distribution rate: {}", distribution_rate);
-    } else {
-        distribution_rate = pool_state.distribution_rate.checked_mul(RATE_MULT).unwrap()
-                                .checked_div(user_stake_entry.initial_distribution_rate).unwrap();
-        msg!("Distribution rate: {}", distribution_rate);
+    if pool_state.epoch != pre_epoch {
+        msg!("Pool wiped out, starting epoch {}", pool_state.epoch);
     }
+    msg!("Rate scale: {}", pool_state.rate_scale);
+
+    Ok(())
+}
+
+pub fn calculate_pending_emissions(pool_state: &PoolState, now: i64) -> Result<u64> {
+    // rewards only stream inside the emission window and since the last update
+    Ok(pool_state.layout().pending_emissions(now).map_err(StakeError::from)?)
+}
 
+pub fn calculate_out_amount(pool_state: &PoolState, user_stake_entry: &StakeEntry) -> Result<u64> {
+    // balance * current rate / rate at the last checkpoint, across at most one scale change, plus boost earnings
+    msg!("Distribution rate: {}", pool_state.distribution_rate);
+    msg!("Initial distribution rate: {}", user_stake_entry.initial_distribution_rate);
     msg!("User staked amount: {}", user_stake_entry.balance);
-    let amount = user_stake_entry.balance;
-    let out_amount: u128;
 
-    out_amount = (amount as u128).checked_mul(distribution_rate).unwrap().checked_div(RATE_MULT).unwrap();
+    let out_amount = pool_state.engine().settled_balance(&user_stake_entry.position()).map_err(StakeError::from)?;
     msg!("Amount after rewards/burn: {}", out_amount);
 
-    out_amount
+    Ok(out_amount)
 }
\ No newline at end of file
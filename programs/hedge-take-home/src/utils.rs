@@ -1,3 +1,19 @@
+use {
+    anchor_lang::prelude::*,
+    crate::state::*,
+    crate::errors::*,
+    anchor_spl::{
+        token_interface::{TokenAccount, MintTo, TokenInterface, Mint, mint_to},
+        token_2022::spl_token_2022::{
+            self,
+            instruction::transfer_checked,
+            state::AccountState,
+            extension::{BaseStateWithExtensions, StateWithExtensions, transfer_fee::{TransferFeeAmount, instruction::harvest_withheld_tokens_to_mint}},
+        },
+    },
+    solana_program::{program::{invoke, invoke_signed}, program_pack::Pack},
+};
+
 /// Helper function for calculating swap fee
 pub fn calculate_fee(
     token_amount: u128,
@@ -16,4 +32,236 @@ pub fn calculate_fee(
             Some(fee)
         }
     }
-}
\ No newline at end of file
+}
+
+/// Helper function for minting rewards streamed since the last update into the vault
+pub fn accrue_emissions<'info>(
+    pool_state: &mut Account<'info, PoolState>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    vault_authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let emissions = calculate_pending_emissions(pool_state, now)?;
+    pool_state.last_emission_update = now;
+
+    // nothing is streamed while the pool is empty, there is nobody to pay. Paused time has no pending emissions
+    if emissions == 0 || pool_state.amount == 0 {
+        return Ok(())
+    }
+    msg!("Streamed rewards to distribute: {}", emissions);
+
+    // program signer seeds
+    let token_mint_key = pool_state.token_mint;
+    let auth_bump = pool_state.vault_auth_bump;
+    let auth_seeds = &[token_mint_key.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let signer = &[&auth_seeds[..]];
+
+    let cpi_accounts = MintTo {
+        mint: token_mint.to_account_info(),
+        to: token_vault.to_account_info(),
+        authority: vault_authority.to_account_info()
+    };
+    mint_to(CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer), emissions)?;
+
+    apply_distribution(pool_state, emissions)
+}
+
+// Token-2022 extension type numbers, spl-token-2022 0.6 doesn't know the ones added after it
+const MINT_CLOSE_AUTHORITY_EXTENSION: u16 = 3;
+const CONFIDENTIAL_TRANSFER_MINT_EXTENSION: u16 = 4;
+const DEFAULT_ACCOUNT_STATE_EXTENSION: u16 = 6;
+const NON_TRANSFERABLE_EXTENSION: u16 = 9;
+const PERMANENT_DELEGATE_EXTENSION: u16 = 12;
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+const CONFIDENTIAL_TRANSFER_FEE_CONFIG_EXTENSION: u16 = 16;
+const CONFIDENTIAL_MINT_BURN_EXTENSION: u16 = 24;
+const PAUSABLE_EXTENSION: u16 = 26;
+
+/// Helper function for rejecting Token-2022 mints with extensions a pool can't safely hold
+pub fn check_mint_extensions(token_mint: &AccountInfo) -> Result<()> {
+    // classic SPL Token mints have no extensions
+    if *token_mint.owner != spl_token_2022::ID {
+        return Ok(())
+    }
+
+    // extension entries follow the base mint, padded to a token account's length, and the account type byte.
+    // They're walked by hand so extensions this spl-token-2022 version can't parse, like metadata, still pass
+    let mint_data = token_mint.try_borrow_data()?;
+    let mut tlv_data = mint_data.get(spl_token_2022::state::Account::LEN + 1..).unwrap_or_default();
+    while let [type_low, type_high, length_low, length_high, rest @ ..] = tlv_data {
+        let extension = u16::from_le_bytes([*type_low, *type_high]);
+        let length = u16::from_le_bytes([*length_low, *length_high]) as usize;
+        let value = rest.get(..length).ok_or(StakeError::InvalidMintExtension)?;
+
+        let unsupported = match extension {
+            // non-transferable tokens, permanent delegates, transfer hooks, pausable mints and confidential
+            // transfers could strand tokens in the vault or move them behind the pool's back, and a closed
+            // mint takes the pool's token with it
+            NON_TRANSFERABLE_EXTENSION | PERMANENT_DELEGATE_EXTENSION | TRANSFER_HOOK_EXTENSION | PAUSABLE_EXTENSION
+            | CONFIDENTIAL_TRANSFER_MINT_EXTENSION | CONFIDENTIAL_TRANSFER_FEE_CONFIG_EXTENSION | CONFIDENTIAL_MINT_BURN_EXTENSION
+            | MINT_CLOSE_AUTHORITY_EXTENSION => true,
+            // vaults created frozen can't receive anything
+            DEFAULT_ACCOUNT_STATE_EXTENSION => value.first() == Some(&(AccountState::Frozen as u8)),
+            // transfer fees are handled by crediting what reaches the vault, the rest don't change how tokens move
+            _ => false
+        };
+        if unsupported {
+            msg!("Unsupported mint extension: {}", extension);
+            return err!(StakeError::InvalidMintExtension)
+        }
+
+        tlv_data = &rest[length..];
+    }
+
+    Ok(())
+}
+
+/// Helper function for moving Token-2022 transfer fees withheld in a pool owned token account back to
+/// its mint, the account can't be closed while it holds any
+pub fn harvest_withheld_fees<'info>(
+    token_program: &AccountInfo<'info>,
+    token_mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+) -> Result<()> {
+    if token_program.key() != spl_token_2022::ID {
+        return Ok(())
+    }
+
+    let withheld_amount = {
+        let account_data = token_account.try_borrow_data()?;
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account_data)?;
+        account.get_extension::<TransferFeeAmount>().map(|fee| u64::from(fee.withheld_amount)).unwrap_or(0)
+    };
+
+    if withheld_amount != 0 {
+        msg!("Harvesting withheld fees: {}", withheld_amount);
+        let harvest_ix = harvest_withheld_tokens_to_mint(&spl_token_2022::ID, &token_mint.key(), &[&token_account.key()])?;
+        invoke(&harvest_ix, &[token_mint.clone(), token_account.clone()])?;
+    }
+
+    Ok(())
+}
+
+/// Helper function for refusing pool states the vault can't pay out, checked after the vault has moved
+pub fn check_solvency<'info>(
+    pool_state: &PoolState,
+    token_vault: &mut InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    // the cached vault balance is stale after a CPI
+    token_vault.reload()?;
+    let expected_amount = pool_state.expected_vault_amount()?;
+    if token_vault.amount < expected_amount {
+        msg!("Vault balance: {}", token_vault.amount);
+        msg!("Expected vault balance: {}", expected_amount);
+        return err!(StakeError::InvariantViolation)
+    }
+
+    Ok(())
+}
+
+/// Helper function for reading an account still in its launch layout, anything else is refused
+pub fn read_legacy_account<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: [u8; 8],
+    legacy_size: usize,
+) -> Result<T> {
+    if account.owner != &crate::ID {
+        return err!(ErrorCode::AccountOwnedByWrongProgram)
+    }
+    if account.data_len() != legacy_size {
+        return err!(StakeError::AccountAlreadyUpgraded)
+    }
+
+    let data = account.try_borrow_data()?;
+    if data[..8] != discriminator {
+        return err!(ErrorCode::AccountDiscriminatorMismatch)
+    }
+
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+/// Helper function for growing a program account in place and writing `state` over it, the payer
+/// tops the account up to the new rent-exempt minimum
+pub fn rewrite_account<'info, T: AccountSerialize>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_size: usize,
+    state: &T,
+) -> Result<()> {
+    let rent_due = Rent::get()?.minimum_balance(new_size).saturating_sub(account.lamports());
+    if rent_due > 0 {
+        msg!("Rent top-up: {}", rent_due);
+        anchor_lang::system_program::transfer(
+            CpiContext::new(system_program.clone(), anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: account.clone()
+            }),
+            rent_due
+        )?;
+    }
+    account.realloc(new_size, true)?;
+
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    state.try_serialize(&mut writer)?;
+
+    Ok(())
+}
+
+/// Helper function for paying tokens out of the pool vault, signed by the vault authority
+pub fn transfer_from_vault<'info>(
+    pool_state: &PoolState,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    vault_authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    // program signer seeds
+    let token_mint_key = pool_state.token_mint;
+    let auth_bump = pool_state.vault_auth_bump;
+    let auth_seeds = &[token_mint_key.as_ref(), VAULT_AUTH_SEED.as_bytes(), &[auth_bump]];
+    let signer = &[&auth_seeds[..]];
+
+    // decimals must match the pool's mint
+    let transfer_ix = transfer_checked(
+        &token_program.key(),
+        &token_vault.key(),
+        &token_mint.key(),
+        &destination.key(),
+        &vault_authority.key(),
+        &[&vault_authority.key()],
+        amount,
+        token_mint.decimals
+    )?;
+
+    invoke_signed(
+        &transfer_ix,
+        &[
+            token_program.to_account_info(),
+            token_vault.to_account_info(),
+            token_mint.to_account_info(),
+            destination.clone(),
+            vault_authority.clone()
+        ],
+        signer
+    )?;
+
+    Ok(())
+}
+
+/// Emits an event to the program logs, and through anchor's event-cpi self-CPI since logs can be
+/// truncated by the runtime and instruction data can't. The accounts struct of `ctx` needs `#[event_cpi]`.
+macro_rules! emit_event {
+    ($ctx:expr, $event:expr) => {{
+        let event = $event;
+        let ctx = &$ctx;
+        emit!(event);
+        emit_cpi!(event);
+    }};
+}
+pub(crate) use emit_event;
\ No newline at end of file
@@ -3,7 +3,7 @@ import { Program } from "@project-serum/anchor";
 import { HedgeTakeHome } from "../target/types/hedge_take_home"
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from '@solana/web3.js'
 import { TOKEN_PROGRAM_ID, createMint, setAuthority, AuthorityType, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
-import { delay, initializeTestUsers, safeAirdrop, MULT, RATE_MULT } from './utils/util'
+import { delay, initializeTestUsers, safeAirdrop, settledBalance, eventAuthority, MULT, RATE_PRECISION } from './utils/util'
 import { userKeypair1, userKeypair2, userKeypair3, programAuthority } from './testKeypairs/testKeypairs'
 import { assert } from "chai"
 import { BN } from "bn.js"
@@ -22,10 +22,7 @@ describe("hedge-take-home", async () => {
   let user3StakeEntry: PublicKey = null
 
 
-  let [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
-    [Buffer.from("vault_authority")],
-    program.programId
-  )
+  let vaultAuthority: PublicKey = null
 
 
   it("Create RND Token mint", async () => {
@@ -42,6 +39,13 @@ describe("hedge-take-home", async () => {
       6,
     )
 
+    // each pool has its own vault authority derived from the mint
+    const [vaultAuth, vaultAuthBump] = await PublicKey.findProgramAddress(
+      [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+      program.programId
+    )
+    vaultAuthority = vaultAuth
+
     // mint RND to test users
     await initializeTestUsers(provider.connection, tokenMint, programAuthority)
 
@@ -79,7 +83,9 @@ describe("hedge-take-home", async () => {
       vaultAuthority: vaultAuthority,
       tokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
-      rent: SYSVAR_RENT_PUBKEY
+      rent: SYSVAR_RENT_PUBKEY,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([programAuthority])
     .rpc()
@@ -89,7 +95,7 @@ describe("hedge-take-home", async () => {
     assert(poolAcct.amount.toNumber() == 0)
     //assert(poolAcct.currentRewardRatio.toNumber() == 1)
     //assert(poolAcct.currentBurnRatio.toNumber() == 1)
-    assert(poolAcct.distributionRate.toNumber() == 1)
+    assert(poolAcct.distributionRate.eq(RATE_PRECISION))
   })
 
   it("Create user stake entry accounts", async () => {
@@ -107,7 +113,9 @@ describe("hedge-take-home", async () => {
       .accounts({
         user: userKeypair1.publicKey,
         userStakeEntry: user1StakeEntry,
-        poolState: pool
+        poolState: pool,
+        eventAuthority: eventAuthority(program),
+        program: program.programId
       })
       .signers([userKeypair1])
       .rpc()
@@ -125,7 +133,9 @@ describe("hedge-take-home", async () => {
       .accounts({
         user: userKeypair2.publicKey,
         userStakeEntry: user2StakeEntry,
-        poolState: pool
+        poolState: pool,
+        eventAuthority: eventAuthority(program),
+        program: program.programId
       })
       .signers([userKeypair2])
       .rpc()
@@ -143,7 +153,9 @@ describe("hedge-take-home", async () => {
       .accounts({
         user: userKeypair3.publicKey,
         userStakeEntry: user3StakeEntry,
-        poolState: pool
+        poolState: pool,
+        eventAuthority: eventAuthority(program),
+        program: program.programId
       })
       .signers([userKeypair3])
       .rpc()
@@ -181,15 +193,20 @@ describe("hedge-take-home", async () => {
     let userEntryAcct = await program.account.stakeEntry.fetch(user1StakeEntry)
     let initialEntryBalance = userEntryAcct.balance
 
-    await program.methods.stake(new BN(200 * MULT))
+    await program.methods.stake(new BN(200 * MULT), new BN(0))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
       user: userKeypair1.publicKey,
       userStakeEntry: user1StakeEntry,
       userTokenAccount: userAta,
+      vaultAuthority: vaultAuthority,
+      tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair1])
     .rpc()
@@ -205,7 +222,7 @@ describe("hedge-take-home", async () => {
     poolAcct = await program.account.poolState.fetch(pool)
     assert(poolAcct.amount.toNumber() / MULT == initialPoolAmt.toNumber() + 200)
     assert(poolAcct.amount.toNumber() == updatedUserEntryAcct.balance.toNumber())
-    assert(poolAcct.distributionRate.toNumber() == updatedUserEntryAcct.initialDistributionRate.toNumber())
+    assert(poolAcct.distributionRate.eq(updatedUserEntryAcct.initialDistributionRate))
   })
 
   it('User 2 stakes RND', async () => {
@@ -223,15 +240,20 @@ describe("hedge-take-home", async () => {
     let userEntryAcct = await program.account.stakeEntry.fetch(user2StakeEntry)
     let initialEntryBalance = userEntryAcct.balance
 
-    await program.methods.stake(new BN(400 * MULT))
+    await program.methods.stake(new BN(400 * MULT), new BN(0))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
       user: userKeypair2.publicKey,
       userStakeEntry: user2StakeEntry,
       userTokenAccount: userAta,
+      vaultAuthority: vaultAuthority,
+      tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair2])
     .rpc()
@@ -246,7 +268,7 @@ describe("hedge-take-home", async () => {
 
     poolAcct = await program.account.poolState.fetch(pool)
     assert(poolAcct.amount.toNumber() == initialPoolAmt.toNumber() + (400*MULT))
-    assert(poolAcct.distributionRate.toNumber() == updatedUserEntryAcct.initialDistributionRate.toNumber())
+    assert(poolAcct.distributionRate.eq(updatedUserEntryAcct.initialDistributionRate))
   })
 
   it('Permissioned RND distribution', async () => {
@@ -264,7 +286,9 @@ describe("hedge-take-home", async () => {
       tokenVault: stakeVault,
       tokenMint: tokenMint,
       mintAuth: vaultAuthority,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([programAuthority])
     .rpc()
@@ -275,10 +299,10 @@ describe("hedge-take-home", async () => {
     vaultAcct = await getAccount(provider.connection, stakeVault)
     assert(vaultAcct.amount == initialVaultAmt + BigInt(30*MULT))
 
-    let tempDistributionRate = 1 + (30*MULT)/initialStakeAmt.toNumber()
-    let distributionRate = tempDistributionRate * initialDistributionRate.toNumber() 
-    console.log("Derived distribution Rate: ", distributionRate)
-    assert(poolAcct.distributionRate.toNumber()/RATE_MULT == distributionRate)
+    // rate grows by (total + rewards) / total
+    let distributionRate = initialDistributionRate.mul(initialStakeAmt.add(new BN(30*MULT))).div(initialStakeAmt)
+    console.log("Derived distribution Rate: ", distributionRate.toString())
+    assert(poolAcct.distributionRate.eq(distributionRate))
   })
 
   it('User 3 stakes RND', async () => {
@@ -296,15 +320,20 @@ describe("hedge-take-home", async () => {
     let userEntryAcct = await program.account.stakeEntry.fetch(user3StakeEntry)
     let initialEntryBalance = userEntryAcct.balance
 
-    await program.methods.stake(new BN(200 * MULT))
+    await program.methods.stake(new BN(200 * MULT), new BN(0))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
       user: userKeypair3.publicKey,
       userStakeEntry: user3StakeEntry,
       userTokenAccount: userAta,
+      vaultAuthority: vaultAuthority,
+      tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair3])
     .rpc()
@@ -319,7 +348,7 @@ describe("hedge-take-home", async () => {
 
     poolAcct = await program.account.poolState.fetch(pool)
     assert(poolAcct.amount.toNumber() == initialPoolAmt.toNumber() + (200*MULT))
-    assert(poolAcct.distributionRate.toNumber() == updatedUserEntryAcct.initialDistributionRate.toNumber())
+    assert(poolAcct.distributionRate.eq(updatedUserEntryAcct.initialDistributionRate))
   })
 
   it('Permissioned RND reward burn', async () => {
@@ -336,7 +365,9 @@ describe("hedge-take-home", async () => {
       tokenVault: stakeVault,
       vaultAuthority: vaultAuthority,
       tokenMint: tokenMint,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([programAuthority])
     .rpc()
@@ -349,8 +380,8 @@ describe("hedge-take-home", async () => {
 
     let burnRate = 1 - (20*MULT)/initialStakeAmt.toNumber()
     console.log("Derived distribution rate: ", burnRate)
-    // console.log("Burn rate on chain: ", poolAcct.currentBurnRatio.toNumber()/RATE_MULT)
-    // assert(poolAcct.currentBurnRatio.toNumber()/RATE_MULT == burnRate)
+    // console.log("Burn rate on chain: ", poolAcct.currentBurnRatio.toNumber()/RATE_PRECISION)
+    // assert(poolAcct.currentBurnRatio.toNumber()/RATE_PRECISION == burnRate)
   })
 
   it('User 1 unstakes RND', async () => {
@@ -366,9 +397,9 @@ describe("hedge-take-home", async () => {
     let initialPoolAmt = Number(poolAcct.amount)
 
     let userEntryAcct = await program.account.stakeEntry.fetch(user1StakeEntry)
-    let initialEntryBalance = Number(userEntryAcct.balance)
+    let expectedAmt = settledBalance(poolAcct, userEntryAcct).toNumber()
 
-    await program.methods.unstake()
+    await program.methods.unstake(new BN(expectedAmt))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
@@ -378,15 +409,16 @@ describe("hedge-take-home", async () => {
       vaultAuthority: vaultAuthority,
       tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair1])
     .rpc()
 
     userTokenAcct = await getAccount(provider.connection, userAta)
     stakeVaultAcct = await getAccount(provider.connection, stakeVault)
-    const distributionRate = (poolAcct.distributionRate.toNumber() - userEntryAcct.initialDistributionRate.toNumber()) / RATE_MULT
-    let expectedAmt = Math.round(initialEntryBalance*distributionRate)
 
     assert(Number(userTokenAcct.amount) == initialUserBalance + expectedAmt)
     assert(Number(stakeVaultAcct.amount) == initialVaultBalance - expectedAmt)
@@ -398,6 +430,71 @@ describe("hedge-take-home", async () => {
     assert(poolAcct.amount.toNumber() == initialPoolAmt - expectedAmt)
   })
 
+  it('User 2 partially unstakes RND', async () => {
+    const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair2.publicKey)
+
+    let userTokenAcct = await getAccount(provider.connection, userAta)
+    let initialUserBalance = Number(userTokenAcct.amount)
+
+    let poolAcct = await program.account.poolState.fetch(pool)
+    let initialPoolAmt = poolAcct.amount.toNumber()
+
+    let userEntryAcct = await program.account.stakeEntry.fetch(user2StakeEntry)
+    const settledAmt = settledBalance(poolAcct, userEntryAcct).toNumber()
+
+    await program.methods.unstake(new BN(50*MULT))
+    .accounts({
+      pool: pool,
+      tokenVault: stakeVault,
+      user: userKeypair2.publicKey,
+      userStakeEntry: user2StakeEntry,
+      userTokenAccount: userAta,
+      vaultAuthority: vaultAuthority,
+      tokenMint: tokenMint,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
+    })
+    .signers([userKeypair2])
+    .rpc()
+
+    userTokenAcct = await getAccount(provider.connection, userAta)
+    assert(Number(userTokenAcct.amount) == initialUserBalance + (50*MULT))
+
+    // remaining balance stays staked from the current distribution rate
+    let updatedUserEntryAcct = await program.account.stakeEntry.fetch(user2StakeEntry)
+    poolAcct = await program.account.poolState.fetch(pool)
+    assert(updatedUserEntryAcct.balance.toNumber() == settledAmt - (50*MULT))
+    assert(updatedUserEntryAcct.initialDistributionRate.eq(poolAcct.distributionRate))
+    assert(poolAcct.amount.toNumber() == initialPoolAmt - (50*MULT))
+
+    // withdrawing more than the settled balance should fail
+    try {
+      await program.methods.unstake(new BN(settledAmt))
+      .accounts({
+        pool: pool,
+        tokenVault: stakeVault,
+        user: userKeypair2.publicKey,
+        userStakeEntry: user2StakeEntry,
+        userTokenAccount: userAta,
+        vaultAuthority: vaultAuthority,
+        tokenMint: tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        positionTokenAccount: program.programId,
+        eventAuthority: eventAuthority(program),
+        program: program.programId
+      })
+      .signers([userKeypair2])
+      .rpc()
+      assert(false, "promise should fail")
+    } catch (e) {
+      assert(e.message.includes("OverdrawError"))
+    }
+  })
+
   it('User 2 adds to staking position', async () => {
     const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair2.publicKey)
 
@@ -410,15 +507,20 @@ describe("hedge-take-home", async () => {
     let poolAcct = await program.account.poolState.fetch(pool)
     let initialPoolAmt = poolAcct.amount
 
-    await program.methods.stake(new BN(15*MULT))
+    await program.methods.stake(new BN(15*MULT), new BN(0))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
       user: userKeypair2.publicKey,
       userStakeEntry: user2StakeEntry,
       userTokenAccount: userAta,
+      vaultAuthority: vaultAuthority,
+      tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair2])
     .rpc()
@@ -433,7 +535,7 @@ describe("hedge-take-home", async () => {
     poolAcct = await program.account.poolState.fetch(pool)
 
     assert(poolAcct.amount.toNumber() == initialPoolAmt.toNumber() + (15*MULT))
-    assert(poolAcct.distributionRate.toNumber() == updatedUserEntryAcct.initialDistributionRate.toNumber())
+    assert(poolAcct.distributionRate.eq(updatedUserEntryAcct.initialDistributionRate))
     console.log("Total in pool state: ", poolAcct.amount.toNumber())
   })
 
@@ -451,7 +553,9 @@ describe("hedge-take-home", async () => {
       tokenVault: stakeVault,
       tokenMint: tokenMint,
       mintAuth: vaultAuthority,
-      tokenProgram: TOKEN_PROGRAM_ID
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([programAuthority])
     .rpc()
@@ -468,8 +572,9 @@ describe("hedge-take-home", async () => {
     let userTokenAcct = await getAccount(provider.connection, userAta)
     let stakeVaultAcct = await getAccount(provider.connection, stakeVault)
     let poolAcct = await program.account.poolState.fetch(pool)
+    let userEntryAcct = await program.account.stakeEntry.fetch(user2StakeEntry)
 
-    await program.methods.unstake()
+    await program.methods.unstake(settledBalance(poolAcct, userEntryAcct))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
@@ -479,7 +584,10 @@ describe("hedge-take-home", async () => {
       vaultAuthority: vaultAuthority,
       tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair2])
     .rpc()
@@ -499,8 +607,9 @@ describe("hedge-take-home", async () => {
 
     let stakeVaultAcct = await getAccount(provider.connection, stakeVault)
     let poolAcct = await program.account.poolState.fetch(pool)
+    let userEntryAcct = await program.account.stakeEntry.fetch(user3StakeEntry)
 
-    await program.methods.unstake()
+    await program.methods.unstake(settledBalance(poolAcct, userEntryAcct))
     .accounts({
       pool: pool,
       tokenVault: stakeVault,
@@ -510,7 +619,10 @@ describe("hedge-take-home", async () => {
       vaultAuthority: vaultAuthority,
       tokenMint: tokenMint,
       tokenProgram: TOKEN_PROGRAM_ID,
-      systemProgram: SystemProgram.programId
+      systemProgram: SystemProgram.programId,
+      positionTokenAccount: program.programId,
+      eventAuthority: eventAuthority(program),
+      program: program.programId
     })
     .signers([userKeypair3])
     .rpc()
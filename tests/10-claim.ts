@@ -0,0 +1,152 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("claim rewards without unstaking", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+    let userEntry: PublicKey = null
+    let userAta: PublicKey = null
+
+    async function claim() {
+        await program.methods.claim()
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    }
+
+    it("Stake into a new pool", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        userEntry = entry
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    })
+
+    it("Claim pays growth above principal", async () => {
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const initialUserBalance = (await getAccount(provider.connection, userAta)).amount
+
+        let event = null
+        const listener = program.addEventListener("Claimed", (e) => { event = e })
+
+        await claim()
+
+        await new Promise((resolve) => setTimeout(resolve, 2000))
+        await program.removeEventListener(listener)
+
+        const userTokenAcct = await getAccount(provider.connection, userAta)
+        assert(userTokenAcct.amount == initialUserBalance + BigInt(10 * MULT))
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 100 * MULT)
+        assert(userEntryAcct.initialDistributionRate.eq(poolAcct.distributionRate))
+        assert(poolAcct.amount.toNumber() == 100 * MULT)
+
+        assert(event != null)
+        assert(event.user.equals(userKeypair1.publicKey))
+        assert(event.amount.toNumber() == 10 * MULT)
+        assert(event.balance.toNumber() == 100 * MULT)
+        assert(event.poolAmount.eq(poolAcct.amount))
+    })
+
+    it("Claim after a burn below principal pays nothing", async () => {
+        await program.methods.burn(new BN(20 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const initialUserBalance = (await getAccount(provider.connection, userAta)).amount
+        const initialEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+
+        await claim()
+
+        const userTokenAcct = await getAccount(provider.connection, userAta)
+        assert(userTokenAcct.amount == initialUserBalance)
+
+        // checkpoint is untouched so the loss is still realized on unstake
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.eq(initialEntryAcct.balance))
+        assert(userEntryAcct.initialDistributionRate.eq(initialEntryAcct.initialDistributionRate))
+    })
+})
\ No newline at end of file
@@ -0,0 +1,94 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("compound staking rewards", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    it("Crank rolls rewards into the stake balance", async () => {
+        const { tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority)
+
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const initialVaultAmt = (await getAccount(provider.connection, stakeVault)).amount
+
+        // sent by the provider wallet, the position owner doesn't sign
+        await program.methods.compound()
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                userStakeEntry: userEntry,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 110 * MULT)
+        assert(userEntryAcct.initialDistributionRate.eq(poolAcct.distributionRate))
+        assert(poolAcct.userDepositAmt.toNumber() == 110 * MULT)
+
+        const vaultAcct = await getAccount(provider.connection, stakeVault)
+        assert(vaultAcct.amount == initialVaultAmt)
+    })
+})
\ No newline at end of file
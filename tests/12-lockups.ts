@@ -0,0 +1,163 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, delay, settledBalance, eventAuthority, MULT, RATE_PRECISION } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+const MAX_LOCK_DURATION = 365 * 24 * 60 * 60
+
+describe("lockups with boosted stake weight", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+    const userEntries: PublicKey[] = []
+
+    it("Stake unlocked and with the maximum lock", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        for (const [user, lockDuration] of [[userKeypair1, 0], [userKeypair2, MAX_LOCK_DURATION]] as [Keypair, number][]) {
+            const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+                [user.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+                program.programId
+            )
+            userEntries.push(userEntry)
+
+            await program.methods.initStakeEntry()
+                .accounts({
+                    user: user.publicKey,
+                    userStakeEntry: userEntry,
+                    poolState: pool,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+
+            const userAta = await getAssociatedTokenAddress(tokenMint, user.publicKey)
+            await program.methods.stake(new BN(100 * MULT), new BN(lockDuration))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: user.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+        }
+
+        const lockedEntryAcct = await program.account.stakeEntry.fetch(userEntries[1])
+        assert(lockedEntryAcct.lockWeightBps.toNumber() == 20_000)
+        assert(lockedEntryAcct.boostWeight.toNumber() == 100 * MULT)
+    })
+
+    it("Locked position earns its boosted share of a distribution", async () => {
+        await program.methods.distribute(new BN(30 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        // 2x weight on half the stake means a 1:2 split of the rewards
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const unlockedEntryAcct = await program.account.stakeEntry.fetch(userEntries[0])
+        const lockedEntryAcct = await program.account.stakeEntry.fetch(userEntries[1])
+        assert(settledBalance(poolAcct, unlockedEntryAcct).toNumber() == 110 * MULT)
+        assert(settledBalance(poolAcct, lockedEntryAcct).toNumber() == 120 * MULT)
+
+        const vaultAcct = await getAccount(provider.connection, stakeVault)
+        assert(Number(vaultAcct.amount) == poolAcct.amount.toNumber() + poolAcct.boostReserve.toNumber())
+    })
+
+    it("Locked position can't be unstaked", async () => {
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair2.publicKey)
+        try {
+            await program.methods.unstake(new BN(1))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair2.publicKey,
+                    userStakeEntry: userEntries[1],
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair2])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: StakeLocked. Error Number: 6014. Error Message: Stake is locked until its lock period ends.")
+        }
+    })
+
+    it("Anyone can poke a lock's weight down to the time left on it", async () => {
+        const pokePosition = (userEntry: PublicKey) => program.methods.pokePosition()
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                userStakeEntry: userEntry,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .rpc()
+
+        // the lock has run down by a few seconds, worth less than a basis point of weight
+        await delay(2000)
+        const initialEntryAcct = await program.account.stakeEntry.fetch(userEntries[1])
+        assert(initialEntryAcct.lockWeightBps.toNumber() == 20_000)
+
+        await pokePosition(userEntries[1])
+
+        // the position is compounded and re-weighted, its boost earnings join the stake
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const lockedEntryAcct = await program.account.stakeEntry.fetch(userEntries[1])
+        assert(lockedEntryAcct.lockWeightBps.toNumber() == 19_999)
+        assert(lockedEntryAcct.balance.toNumber() == 120 * MULT)
+        assert(lockedEntryAcct.boostWeight.toNumber() == 120 * MULT * 9_999 / 10_000)
+        assert(lockedEntryAcct.lockEnd.eq(initialEntryAcct.lockEnd))
+        // the pool's total boost weight is now the locked position's reduced weight alone
+        assert(poolAcct.totalBoostWeight.eq(lockedEntryAcct.boostWeight.mul(RATE_PRECISION).div(lockedEntryAcct.initialDistributionRate)))
+        assert(poolAcct.boostReserve.toNumber() == 0)
+
+        // an unlocked position has no boost to take off, nothing changes
+        const unlockedEntryAcct = await program.account.stakeEntry.fetch(userEntries[0])
+        await pokePosition(userEntries[0])
+        const unlockedEntryAcctAfter = await program.account.stakeEntry.fetch(userEntries[0])
+        assert(unlockedEntryAcctAfter.balance.eq(unlockedEntryAcct.balance))
+        assert(unlockedEntryAcctAfter.initialDistributionRate.eq(unlockedEntryAcct.initialDistributionRate))
+    })
+})
\ No newline at end of file
@@ -0,0 +1,239 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, settledBalance, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("unbonding queue for withdrawals", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+    let userEntry: PublicKey = null
+    let userAta: PublicKey = null
+
+    it("Unbonding tokens are not burned", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        await program.methods.setUnbondingPeriod(new BN(60 * 60))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        userEntry = entry
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.requestUnstake(new BN(40 * MULT))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.amount.toNumber() == 60 * MULT)
+        assert(poolAcct.pendingWithdrawal.toNumber() == 40 * MULT)
+
+        await program.methods.burn(new BN(30 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        // the burn only hits the 60 tokens still staked
+        poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(poolAcct.pendingWithdrawal.toNumber() == 40 * MULT)
+        assert(userEntryAcct.unbondingAmount.toNumber() == 40 * MULT)
+        assert(settledBalance(poolAcct, userEntryAcct).toNumber() == 30 * MULT)
+
+        const vaultAcct = await getAccount(provider.connection, stakeVault)
+        assert(Number(vaultAcct.amount) == poolAcct.amount.toNumber() + poolAcct.pendingWithdrawal.toNumber())
+    })
+
+    it("Can't withdraw before the unbonding period ends", async () => {
+        try {
+            await program.methods.withdraw()
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: UnbondingNotComplete. Error Number: 6018. Error Message: Unbonding period has not ended yet.")
+        }
+    })
+
+    it("Can't skip the unbonding period with unstake", async () => {
+        try {
+            await program.methods.unstake(new BN(10 * MULT))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: UnbondingRequired. Error Number: 6016. Error Message: Pool has an unbonding period, use request_unstake and withdraw.")
+        }
+    })
+
+    it("Withdraw unbonded tokens", async () => {
+        // with no cooldown the tokens can be withdrawn right after the request
+        await program.methods.setUnbondingPeriod(new BN(0))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        let requested = null
+        let withdrawn = null
+        const requestListener = program.addEventListener("UnstakeRequested", (e) => { requested = e })
+        const withdrawListener = program.addEventListener("Withdrawn", (e) => { withdrawn = e })
+
+        await program.methods.requestUnstake(new BN(10 * MULT))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        let userTokenAcct = await getAccount(provider.connection, userAta)
+        const initialUserBalance = userTokenAcct.amount
+
+        await program.methods.withdraw()
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await new Promise((resolve) => setTimeout(resolve, 2000))
+        await program.removeEventListener(requestListener)
+        await program.removeEventListener(withdrawListener)
+
+        userTokenAcct = await getAccount(provider.connection, userAta)
+        assert(userTokenAcct.amount == initialUserBalance + BigInt(50 * MULT))
+
+        assert(requested != null)
+        assert(requested.amount.toNumber() == 10 * MULT)
+        assert(requested.unbondingAmount.toNumber() == 50 * MULT)
+        assert(requested.pendingWithdrawal.toNumber() == 50 * MULT)
+        assert(withdrawn != null)
+        assert(withdrawn.amount.toNumber() == 50 * MULT)
+        assert(withdrawn.pendingWithdrawal.toNumber() == 0)
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(poolAcct.pendingWithdrawal.toNumber() == 0)
+        assert(userEntryAcct.unbondingAmount.toNumber() == 0)
+        assert(poolAcct.amount.toNumber() == 20 * MULT)
+    })
+})
\ No newline at end of file
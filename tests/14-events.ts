@@ -0,0 +1,79 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("events for pool state transitions", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    it("Distribute emits a Distributed event", async () => {
+        const { tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority)
+
+        let event = null
+        const listener = program.addEventListener("Distributed", (e) => { event = e })
+
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc({ commitment: "confirmed" })
+
+        await new Promise((resolve) => setTimeout(resolve, 2000))
+        await program.removeEventListener(listener)
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(event != null)
+        assert(event.pool.equals(pool))
+        assert(event.amount.toNumber() == 10 * MULT)
+        assert(event.postDistributionRate.eq(poolAcct.distributionRate))
+        assert(event.poolAmount.eq(poolAcct.amount))
+        assert(event.userDepositAmt.eq(poolAcct.userDepositAmt))
+    })
+
+    it("Events are also emitted through anchor's event-cpi self-CPI", async () => {
+        const { tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority)
+
+        const txid = await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc({ commitment: "confirmed" })
+
+        const tx = await provider.connection.getTransaction(txid, { commitment: "confirmed" })
+        const innerIxs = tx.meta.innerInstructions.flatMap((inner) => inner.instructions)
+        const accountKeys = tx.transaction.message.accountKeys
+        const logIx = innerIxs.find((ix) => accountKeys[ix.programIdIndex].equals(program.programId))
+        assert(logIx != null)
+        assert(accountKeys[logIx.accounts[0]].equals(eventAuthority(program)))
+
+        // instruction data is anchor's event-cpi tag, then the event discriminator and the borsh encoded event
+        const ixData = anchor.utils.bytes.bs58.decode(logIx.data)
+        const event = program.coder.events.decode(anchor.utils.bytes.base64.encode(ixData.slice(8)))
+        assert(event.name == "Distributed")
+        assert(event.data.amount.toNumber() == 10 * MULT)
+    })
+})
\ No newline at end of file
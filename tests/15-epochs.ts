@@ -0,0 +1,140 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, settledBalance, eventAuthority, MULT, RATE_PRECISION } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("new epoch after the whole pool is burned", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+
+    async function stake(user: Keypair, amount: number): Promise<PublicKey> {
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [user.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: user.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([user])
+            .rpc()
+
+        const userAta = await getAssociatedTokenAddress(tokenMint, user.publicKey)
+        await program.methods.stake(new BN(amount), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: user.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([user])
+            .rpc()
+
+        return userEntry
+    }
+
+    it("Full burn starts a new epoch and new stakers keep working", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const oldEntry = await stake(userKeypair1, 100 * MULT)
+
+        await program.methods.burn(new BN(100 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.epoch.toNumber() == 1)
+        assert(poolAcct.amount.toNumber() == 0)
+        assert(poolAcct.distributionRate.eq(RATE_PRECISION))
+
+        // the old position settles to zero instead of dividing by a zero rate
+        let oldEntryAcct = await program.account.stakeEntry.fetch(oldEntry)
+        assert(settledBalance(poolAcct, oldEntryAcct).toNumber() == 0)
+
+        const newEntry = await stake(userKeypair2, 50 * MULT)
+
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        poolAcct = await program.account.poolState.fetch(pool)
+        const newEntryAcct = await program.account.stakeEntry.fetch(newEntry)
+        oldEntryAcct = await program.account.stakeEntry.fetch(oldEntry)
+        assert(settledBalance(poolAcct, newEntryAcct).toNumber() == 60 * MULT)
+        assert(settledBalance(poolAcct, oldEntryAcct).toNumber() == 0)
+
+        // the old position can still be closed out cleanly
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+        await program.methods.unstake(new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: oldEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        oldEntryAcct = await program.account.stakeEntry.fetch(oldEntry)
+        assert(oldEntryAcct.balance.toNumber() == 0)
+        assert(oldEntryAcct.initialEpoch.toNumber() == 1)
+
+        poolAcct = await program.account.poolState.fetch(pool)
+        const vaultAcct = await getAccount(provider.connection, stakeVault)
+        assert(Number(vaultAcct.amount) == poolAcct.amount.toNumber())
+        assert(poolAcct.userDepositAmt.toNumber() == 50 * MULT)
+    })
+})
\ No newline at end of file
@@ -0,0 +1,136 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, settledBalance, viewSettledBalance, eventAuthority, MULT, RATE_PRECISION } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("view position quotes", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    it("Quote matches what unstake pays after a distribution and a burn", async () => {
+        const { tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority)
+
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        // simulated, nobody signs and nothing is written
+        let view = await program.methods.viewPosition()
+            .accounts({
+                pool: pool,
+                userStakeEntry: userEntry
+            })
+            .view()
+        assert(view.principal.toNumber() == 100 * MULT)
+        assert(view.settledBalance.toNumber() == 110 * MULT)
+        assert(view.accruedRewards.toNumber() == 10 * MULT)
+        assert(view.burnedAmount.toNumber() == 0)
+        assert(view.effectiveRate.eq(RATE_PRECISION.muln(11).divn(10)))
+
+        await program.methods.burn(new BN(22 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        view = await program.methods.viewPosition()
+            .accounts({
+                pool: pool,
+                userStakeEntry: userEntry
+            })
+            .view()
+        assert(view.settledBalance.toNumber() == 88 * MULT)
+        assert(view.accruedRewards.toNumber() == 0)
+        assert(view.burnedAmount.toNumber() == 12 * MULT)
+
+        // the program's quote agrees with the independent computation from the fetched accounts
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(settledBalance(poolAcct, userEntryAcct).toNumber() == 88 * MULT)
+        assert((await viewSettledBalance(program, pool, userEntry)).eq(settledBalance(poolAcct, userEntryAcct)))
+
+        const initialUserAmt = (await getAccount(provider.connection, userAta)).amount
+        await program.methods.unstake(view.settledBalance)
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const userAcct = await getAccount(provider.connection, userAta)
+        assert(userAcct.amount - initialUserAmt == BigInt(88 * MULT))
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 0)
+    })
+})
\ No newline at end of file
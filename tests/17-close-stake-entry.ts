@@ -0,0 +1,199 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("close stake entry", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let userEntry: PublicKey
+    let userAta: PublicKey
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        userEntry = entry
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    })
+
+    it("Entry with a balance can't be closed without the vault accounts", async () => {
+        try {
+            await program.methods.closeStakeEntry()
+                .accounts({
+                    pool: pool,
+                    tokenMint: tokenMint,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    positionTokenAccount: program.programId,
+                    tokenVault: program.programId,
+                    userTokenAccount: program.programId,
+                    vaultAuthority: program.programId,
+                    tokenProgram: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: StakeEntryNotEmpty. Error Number: 6023. Error Message: Stake entry still has a balance, unbonding tokens or unclaimed rewards.")
+        }
+    })
+
+    it("Empty entry is closed and its rent returned to the user", async () => {
+        await program.methods.unstake(new BN(100 * MULT))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const entryLamports = await provider.connection.getBalance(userEntry)
+        const initialUserLamports = await provider.connection.getBalance(userKeypair1.publicKey)
+
+        await program.methods.closeStakeEntry()
+            .accounts({
+                pool: pool,
+                tokenMint: tokenMint,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                positionTokenAccount: program.programId,
+                tokenVault: program.programId,
+                userTokenAccount: program.programId,
+                vaultAuthority: program.programId,
+                tokenProgram: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc({ commitment: "confirmed" })
+
+        const entryInfo = await provider.connection.getAccountInfo(userEntry, "confirmed")
+        assert(entryInfo == null)
+        // the user also paid the transaction fee
+        const userLamports = await provider.connection.getBalance(userKeypair1.publicKey, "confirmed")
+        assert(userLamports > initialUserLamports)
+        assert(userLamports <= initialUserLamports + entryLamports)
+    })
+
+    it("Entry can be initialized again under the same seeds", async () => {
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 0)
+        assert(userEntryAcct.initialDistributionRate.eq(poolAcct.distributionRate))
+    })
+
+    it("Dust staked for the user is paid out and the entry closed in one step", async () => {
+        // anyone can stake_for into the entry, that mustn't keep the user from closing it
+        await program.methods.stakeFor(new BN(1), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                funder: userKeypair2.publicKey,
+                beneficiary: userKeypair1.publicKey,
+                beneficiaryStakeEntry: userEntry,
+                funderTokenAccount: await getAssociatedTokenAddress(tokenMint, userKeypair2.publicKey),
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair2])
+            .rpc()
+
+        const initialUserAmount = (await getAccount(provider.connection, userAta)).amount
+        await program.methods.closeStakeEntry()
+            .accounts({
+                pool: pool,
+                tokenMint: tokenMint,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                positionTokenAccount: program.programId,
+                tokenVault: stakeVault,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc({ commitment: "confirmed" })
+
+        assert(await provider.connection.getAccountInfo(userEntry, "confirmed") == null)
+        const userAmount = (await getAccount(provider.connection, userAta, "confirmed")).amount
+        assert(userAmount == initialUserAmount + BigInt(1))
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.amount.toNumber() == 0)
+        assert(poolAcct.userDepositAmt.toNumber() == 0)
+    })
+})
\ No newline at end of file
@@ -0,0 +1,422 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAssociatedTokenAddress } from '@solana/spl-token'
+import { createTestPool, settledBalance, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("pool shutdown", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let rewardMint: PublicKey
+    let rewardVault: PublicKey
+    const users = [userKeypair1, userKeypair2]
+    const userEntries: PublicKey[] = []
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        rewardMint = await createMint(
+            provider.connection,
+            programAuthority,
+            programAuthority.publicKey,
+            programAuthority.publicKey,
+            6,
+        )
+        const [vault, vaultBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), rewardMint.toBuffer(), Buffer.from("reward_vault")],
+            program.programId
+        )
+        rewardVault = vault
+
+        await program.methods.addRewardMint()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        for (const [user, amount] of [[userKeypair1, 100], [userKeypair2, 200]] as [anchor.web3.Keypair, number][]) {
+            const [entry, entryBump] = await PublicKey.findProgramAddress(
+                [user.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+                program.programId
+            )
+            userEntries.push(entry)
+
+            await program.methods.initStakeEntry()
+                .accounts({
+                    user: user.publicKey,
+                    userStakeEntry: entry,
+                    poolState: pool,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+
+            await program.methods.stake(new BN(amount * MULT), new BN(0))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: user.publicKey,
+                    userStakeEntry: entry,
+                    userTokenAccount: await getAssociatedTokenAddress(tokenMint, user.publicKey),
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+        }
+
+        // 10 RND over 300 staked doesn't split evenly, the rounding dust stays in the pool
+        await program.methods.distribute(new BN(10 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const funderAta = await createAssociatedTokenAccount(
+            provider.connection,
+            programAuthority,
+            rewardMint,
+            programAuthority.publicKey
+        )
+        await mintTo(
+            provider.connection,
+            programAuthority,
+            rewardMint,
+            funderAta,
+            programAuthority,
+            30 * MULT
+        )
+        await program.methods.distributeReward(new BN(30 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                funderTokenAccount: funderAta,
+                tokenProgram: TOKEN_PROGRAM_ID
+            })
+            .signers([programAuthority])
+            .rpc()
+    })
+
+    it("Active pool can't be closed", async () => {
+        try {
+            await program.methods.closePool()
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    rewardTokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool_state. Error Code: PoolActive. Error Number: 6025. Error Message: Pool must be deactivated first.")
+        }
+    })
+
+    it("Deactivated pool rejects stakes and distributions", async () => {
+        await program.methods.deactivatePool()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.deactivated)
+
+        try {
+            await program.methods.stake(new BN(10 * MULT), new BN(0))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntries[0],
+                    userTokenAccount: await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey),
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool. Error Code: PoolDeactivated. Error Number: 6024. Error Message: Pool has been deactivated.")
+        }
+
+        try {
+            await program.methods.distribute(new BN(10 * MULT))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    tokenMint: tokenMint,
+                    mintAuth: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool_state. Error Code: PoolDeactivated. Error Number: 6024. Error Message: Pool has been deactivated.")
+        }
+    })
+
+    it("Pool can't be closed while users are staked", async () => {
+        try {
+            await program.methods.closePool()
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    rewardTokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: PoolNotEmpty. Error Number: 6026. Error Message: Pool still has staked or unbonding tokens.")
+        }
+    })
+
+    it("Pool can't be closed while reward mint earnings are unclaimed", async () => {
+        for (let i = 0; i < users.length; i++) {
+            const poolAcct = await program.account.poolState.fetch(pool)
+            const userEntryAcct = await program.account.stakeEntry.fetch(userEntries[i])
+            await program.methods.unstake(settledBalance(poolAcct, userEntryAcct))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: users[i].publicKey,
+                    userStakeEntry: userEntries[i],
+                    userTokenAccount: await getAssociatedTokenAddress(tokenMint, users[i].publicKey),
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([users[i]])
+                .rpc()
+        }
+
+        // everyone is out, only rounding dust is left staked
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.userDepositAmt.toNumber() == 0)
+        assert(poolAcct.amount.toNumber() > 0)
+        assert(poolAcct.rewardsOwedTotal[0].toNumber() == 30 * MULT)
+
+        try {
+            await program.methods.closePool()
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    rewardTokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .remainingAccounts([
+                    { pubkey: rewardVault, isWritable: true, isSigner: false },
+                    { pubkey: rewardMint, isWritable: true, isSigner: false }
+                ])
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: RewardsOutstanding. Error Number: 6032. Error Message: Reward mint earnings haven't all been claimed.")
+        }
+    })
+
+    it("Drained pool burns its dust and closes its vaults and state", async () => {
+        for (let i = 0; i < users.length; i++) {
+            const userRewardAta = await createAssociatedTokenAccount(
+                provider.connection,
+                users[i],
+                rewardMint,
+                users[i].publicKey
+            )
+
+            await program.methods.claimRewards()
+                .accounts({
+                    pool: pool,
+                    user: users[i].publicKey,
+                    userStakeEntry: userEntries[i],
+                    vaultAuthority: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    positionTokenAccount: program.programId
+                })
+                .remainingAccounts([
+                    { pubkey: rewardVault, isWritable: true, isSigner: false },
+                    { pubkey: rewardMint, isWritable: false, isSigner: false },
+                    { pubkey: userRewardAta, isWritable: true, isSigner: false }
+                ])
+                .signers([users[i]])
+                .rpc()
+        }
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.rewardsOwedTotal[0].toNumber() == 0)
+
+        const rent = await provider.connection.getBalance(pool) + await provider.connection.getBalance(stakeVault) + await provider.connection.getBalance(rewardVault)
+        const initialAuthorityLamports = await provider.connection.getBalance(programAuthority.publicKey)
+
+        await program.methods.closePool()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                rewardTokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .remainingAccounts([
+                { pubkey: rewardVault, isWritable: true, isSigner: false },
+                { pubkey: rewardMint, isWritable: true, isSigner: false }
+            ])
+            .signers([programAuthority])
+            .rpc({ commitment: "confirmed" })
+
+        assert(await provider.connection.getAccountInfo(pool, "confirmed") == null)
+        assert(await provider.connection.getAccountInfo(stakeVault, "confirmed") == null)
+        assert(await provider.connection.getAccountInfo(rewardVault, "confirmed") == null)
+        const authorityLamports = await provider.connection.getBalance(programAuthority.publicKey, "confirmed")
+        assert(authorityLamports > initialAuthorityLamports)
+        assert(authorityLamports <= initialAuthorityLamports + rent)
+    })
+
+    it("Entries of a closed pool can still be closed", async () => {
+        await program.methods.closeStakeEntry()
+            .accounts({
+                pool: pool,
+                tokenMint: tokenMint,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntries[0],
+                positionTokenAccount: program.programId,
+                tokenVault: program.programId,
+                userTokenAccount: program.programId,
+                vaultAuthority: program.programId,
+                tokenProgram: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc({ commitment: "confirmed" })
+
+        assert(await provider.connection.getAccountInfo(userEntries[0], "confirmed") == null)
+    })
+
+    it("Entries of a closed pool can be closed after its address is initialized again", async () => {
+        await program.methods.initPool()
+            .accounts({
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                programAuthority: programAuthority.publicKey,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        // the entry still holds reward checkpoints from the closed pool, the new one starts from scratch
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntries[1])
+        assert(!poolAcct.generation.eq(userEntryAcct.poolGeneration))
+        assert(userEntryAcct.rewardCheckpoints[0].gt(poolAcct.rewardInfos[0].rewardRate))
+
+        await program.methods.closeStakeEntry()
+            .accounts({
+                pool: pool,
+                tokenMint: tokenMint,
+                user: userKeypair2.publicKey,
+                userStakeEntry: userEntries[1],
+                positionTokenAccount: program.programId,
+                tokenVault: program.programId,
+                userTokenAccount: program.programId,
+                vaultAuthority: program.programId,
+                tokenProgram: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair2])
+            .rpc({ commitment: "confirmed" })
+
+        assert(await provider.connection.getAccountInfo(userEntries[1], "confirmed") == null)
+    })
+})
\ No newline at end of file
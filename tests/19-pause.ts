@@ -0,0 +1,320 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, createMint, createAssociatedTokenAccount, mintTo } from '@solana/spl-token'
+import { createTestPool, delay, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+const PAUSE_STAKE = 1 << 0
+const PAUSE_UNSTAKE = 1 << 1
+const PAUSE_DISTRIBUTE = 1 << 2
+const PAUSE_BURN = 1 << 3
+
+describe("pause switches", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let userEntry: PublicKey
+    let userAta: PublicKey
+
+    const stake = (amount: number) => program.methods.stake(new BN(amount), new BN(0))
+        .accounts({
+            pool: pool,
+            tokenVault: stakeVault,
+            user: userKeypair1.publicKey,
+            userStakeEntry: userEntry,
+            userTokenAccount: userAta,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            positionTokenAccount: program.programId,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([userKeypair1])
+        .rpc()
+
+    const setPause = (paused: number) => program.methods.setPause(paused)
+        .accounts({
+            programAuthority: programAuthority.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([programAuthority])
+        .rpc()
+
+    const setEmissions = (rate: number, start: number, end: number) => program.methods.setEmissions(new BN(rate), new BN(start), new BN(end))
+        .accounts({
+            programAuthority: programAuthority.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([programAuthority])
+        .rpc()
+
+    const crankEmissions = () => program.methods.crankEmissions()
+        .accounts({
+            poolState: pool,
+            tokenVault: stakeVault,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        userEntry = entry
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await stake(100 * MULT)
+    })
+
+    it("Paused stake fails while other instructions keep working", async () => {
+        await setPause(PAUSE_STAKE | PAUSE_BURN)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.paused == (PAUSE_STAKE | PAUSE_BURN))
+
+        try {
+            await stake(10 * MULT)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        try {
+            await program.methods.burn(new BN(10 * MULT))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    tokenMint: tokenMint,
+                    vaultAuthority: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool_state. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        // users can still leave
+        await program.methods.unstake(new BN(50 * MULT))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 50 * MULT)
+    })
+
+    it("Clearing the flag unpauses", async () => {
+        await setPause(0)
+        await stake(10 * MULT)
+
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 60 * MULT)
+    })
+
+    it("Claims and compounding follow the unstake and stake flags", async () => {
+        await setPause(PAUSE_STAKE | PAUSE_UNSTAKE)
+
+        try {
+            await program.methods.claim()
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        try {
+            await program.methods.compound()
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    userStakeEntry: userEntry,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        await setPause(0)
+    })
+
+    it("Nothing streams while distributions are paused", async () => {
+        const now = await provider.connection.getBlockTime(await provider.connection.getSlot())
+        await setEmissions(MULT, now, now + 3600)
+        await setPause(PAUSE_DISTRIBUTE)
+        const initialPoolAcct = await program.account.poolState.fetch(pool)
+
+        await delay(3000)
+
+        try {
+            await crankEmissions()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool_state. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        // manual reward mint distributions are paused as well
+        const rewardMint = await createMint(provider.connection, programAuthority, programAuthority.publicKey, null, 6)
+        const [rewardVault, rewardVaultBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), rewardMint.toBuffer(), Buffer.from("reward_vault")],
+            program.programId
+        )
+        await program.methods.addRewardMint()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY
+            })
+            .signers([programAuthority])
+            .rpc()
+        const funderAta = await createAssociatedTokenAccount(provider.connection, programAuthority, rewardMint, programAuthority.publicKey)
+        await mintTo(provider.connection, programAuthority, rewardMint, funderAta, programAuthority, 10 * MULT)
+
+        try {
+            await program.methods.distributeReward(new BN(10 * MULT))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    rewardMint: rewardMint,
+                    rewardVault: rewardVault,
+                    funderTokenAccount: funderAta,
+                    tokenProgram: TOKEN_PROGRAM_ID
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool_state. Error Code: Paused. Error Number: 6027. Error Message: Instruction is paused for this pool.")
+        }
+
+        // staking still touches the pool, the paused time is skipped instead of streamed
+        await stake(10 * MULT)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.distributionRate.eq(initialPoolAcct.distributionRate))
+        assert(poolAcct.amount.toNumber() == initialPoolAcct.amount.toNumber() + 10 * MULT)
+        assert(poolAcct.lastEmissionUpdate.gt(initialPoolAcct.lastEmissionUpdate))
+
+        await setEmissions(0, 0, 0)
+        await setPause(0)
+    })
+
+    it("Unpausing doesn't pay out the paused time", async () => {
+        const now = await provider.connection.getBlockTime(await provider.connection.getSlot())
+        await setEmissions(MULT, now, now + 3600)
+        await setPause(PAUSE_DISTRIBUTE)
+        const pausedPoolAcct = await program.account.poolState.fetch(pool)
+
+        // nothing touches the pool while it is paused
+        await delay(4000)
+        await setPause(0)
+        const unpausedPoolAcct = await program.account.poolState.fetch(pool)
+        assert(unpausedPoolAcct.amount.eq(pausedPoolAcct.amount))
+        assert(unpausedPoolAcct.distributionRate.eq(pausedPoolAcct.distributionRate))
+        assert(unpausedPoolAcct.lastEmissionUpdate.toNumber() >= pausedPoolAcct.lastEmissionUpdate.toNumber() + 4)
+
+        await crankEmissions()
+        const poolAcct = await program.account.poolState.fetch(pool)
+
+        // only the seconds since unpausing streamed, and the rate grew by exactly that much
+        const unpausedSeconds = poolAcct.lastEmissionUpdate.sub(unpausedPoolAcct.lastEmissionUpdate).toNumber()
+        const streamed = poolAcct.amount.sub(unpausedPoolAcct.amount)
+        assert(streamed.eq(new BN(unpausedSeconds * MULT)))
+        const expectedRate = unpausedPoolAcct.distributionRate.mul(poolAcct.amount).div(unpausedPoolAcct.amount)
+        assert(poolAcct.distributionRate.sub(expectedRate).abs().lte(new BN(1)))
+
+        await setEmissions(0, 0, 0)
+    })
+
+    it("Unknown pause bits are rejected", async () => {
+        try {
+            await setPause(1 << 4)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: InvalidPauseFlags. Error Number: 6028. Error Message: Pause flags contain unknown bits.")
+        }
+    })
+})
\ No newline at end of file
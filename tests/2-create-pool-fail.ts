@@ -3,7 +3,7 @@ import { Program } from "@project-serum/anchor";
 import { HedgeTakeHome } from "../target/types/hedge_take_home"
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
 import { TOKEN_PROGRAM_ID, createMint, setAuthority, AuthorityType } from '@solana/spl-token'
-import { delay, initializeTestUsers, safeAirdrop } from './utils/util'
+import { delay, initializeTestUsers, safeAirdrop, eventAuthority } from './utils/util'
 import { incorrectProgramAuthority } from './testKeypairs/testKeypairs'
 import { expect } from "chai"
 
@@ -22,10 +22,7 @@ describe("test persmissioned instructions", async () => {
     const programAuthority = incorrectProgramAuthority
 
 
-    let [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
-        [Buffer.from("vault_authority")],
-        program.programId
-    )
+    let vaultAuthority: PublicKey = null
 
     it("Create stake pool with incorrect program authority", async () => {
         await safeAirdrop(programAuthority.publicKey, provider.connection)
@@ -40,6 +37,13 @@ describe("test persmissioned instructions", async () => {
             9,
         )
         
+        // each pool has its own vault authority derived from the mint
+        const [vaultAuth, vaultAuthBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+            program.programId
+        )
+        vaultAuthority = vaultAuth
+
         // mint RND to test users
         await initializeTestUsers(provider.connection, tokenMint, programAuthority)
         
@@ -75,7 +79,9 @@ describe("test persmissioned instructions", async () => {
                 vaultAuthority: vaultAuthority,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
-                rent: SYSVAR_RENT_PUBKEY
+                rent: SYSVAR_RENT_PUBKEY,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .signers([programAuthority])
                 .rpc()
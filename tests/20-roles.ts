@@ -0,0 +1,265 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, createMint, createAssociatedTokenAccount, mintTo, getAccount } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+const PAUSE_STAKE = 1 << 0
+
+describe("pool roles", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    const distributor = Keypair.generate()
+    const burner = Keypair.generate()
+    const guardian = Keypair.generate()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+
+    const distribute = (signer: Keypair) => program.methods.distribute(new BN(10 * MULT))
+        .accounts({
+            programAuthority: signer.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            tokenMint: tokenMint,
+            mintAuth: vaultAuthority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([signer])
+        .rpc()
+
+    const setEmissions = (signer: Keypair) => program.methods.setEmissions(new BN(0), new BN(0), new BN(0))
+        .accounts({
+            programAuthority: signer.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([signer])
+        .rpc()
+
+    const burn = (signer: Keypair) => program.methods.burn(new BN(10 * MULT))
+        .accounts({
+            programAuthority: signer.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            tokenMint: tokenMint,
+            vaultAuthority: vaultAuthority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([signer])
+        .rpc()
+
+    const setPause = (signer: Keypair, paused: number) => program.methods.setPause(paused)
+        .accounts({
+            programAuthority: signer.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([signer])
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    })
+
+    it("Roles start with the pool authority and can be handed out", async () => {
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.distributor.equals(programAuthority.publicKey))
+        assert(poolAcct.burner.equals(programAuthority.publicKey))
+        assert(poolAcct.guardian.equals(programAuthority.publicKey))
+
+        await program.methods.setPoolRoles(distributor.publicKey, burner.publicKey, guardian.publicKey)
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.distributor.equals(distributor.publicKey))
+        assert(poolAcct.burner.equals(burner.publicKey))
+        assert(poolAcct.guardian.equals(guardian.publicKey))
+    })
+
+    it("Only the distributor can distribute", async () => {
+        for (const signer of [programAuthority, burner]) {
+            try {
+                await distribute(signer)
+                expect(true, "promise should fail").eq(false)
+            } catch (e) {
+                console.log(e.message)
+                expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+            }
+        }
+
+        await distribute(distributor)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.amount.toNumber() == 110 * MULT)
+    })
+
+    it("Only the distributor can distribute reward mints", async () => {
+        const rewardMint = await createMint(provider.connection, programAuthority, programAuthority.publicKey, null, 6)
+        const [rewardVault, rewardVaultBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), rewardMint.toBuffer(), Buffer.from("reward_vault")],
+            program.programId
+        )
+        await program.methods.addRewardMint()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const distributeReward = async (signer: Keypair) => {
+            const funderAta = await createAssociatedTokenAccount(provider.connection, programAuthority, rewardMint, signer.publicKey)
+            await mintTo(provider.connection, programAuthority, rewardMint, funderAta, programAuthority, 10 * MULT)
+
+            await program.methods.distributeReward(new BN(10 * MULT))
+                .accounts({
+                    programAuthority: signer.publicKey,
+                    poolState: pool,
+                    rewardMint: rewardMint,
+                    rewardVault: rewardVault,
+                    funderTokenAccount: funderAta,
+                    tokenProgram: TOKEN_PROGRAM_ID
+                })
+                .signers([signer])
+                .rpc()
+        }
+
+        // the pool authority funds it but is no longer the distributor
+        for (const signer of [programAuthority, burner]) {
+            try {
+                await distributeReward(signer)
+                expect(true, "promise should fail").eq(false)
+            } catch (e) {
+                console.log(e.message)
+                expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+            }
+        }
+
+        await distributeReward(distributor)
+        const vaultAcct = await getAccount(provider.connection, rewardVault)
+        assert(Number(vaultAcct.amount) == 10 * MULT)
+    })
+
+    it("Only the distributor can set emissions", async () => {
+        for (const signer of [programAuthority, burner]) {
+            try {
+                await setEmissions(signer)
+                expect(true, "promise should fail").eq(false)
+            } catch (e) {
+                console.log(e.message)
+                expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+            }
+        }
+
+        await setEmissions(distributor)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.emissionRate.toNumber() == 0)
+    })
+
+    it("Only the burner can burn", async () => {
+        for (const signer of [programAuthority, distributor]) {
+            try {
+                await burn(signer)
+                expect(true, "promise should fail").eq(false)
+            } catch (e) {
+                console.log(e.message)
+                expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+            }
+        }
+
+        await burn(burner)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.amount.toNumber() == 100 * MULT)
+    })
+
+    it("Guardian can pause but not unpause", async () => {
+        await setPause(guardian, PAUSE_STAKE)
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.paused == PAUSE_STAKE)
+
+        try {
+            await setPause(guardian, 0)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+        }
+
+        try {
+            await setPause(distributor, PAUSE_STAKE)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+        }
+
+        await setPause(programAuthority, 0)
+        poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.paused == 0)
+    })
+})
\ No newline at end of file
@@ -0,0 +1,192 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { Keypair, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, Transaction, sendAndConfirmTransaction } from '@solana/web3.js'
+import {
+    TOKEN_2022_PROGRAM_ID,
+    ExtensionType,
+    getMintLen,
+    createInitializeMintInstruction,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializeNonTransferableMintInstruction,
+    createInitializeMintCloseAuthorityInstruction,
+    createAssociatedTokenAccount,
+    mintTo,
+    setAuthority,
+    AuthorityType
+} from '@solana/spl-token'
+import { eventAuthority, MULT, safeAirdrop } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+const FEE_BPS = 100
+
+describe("token-2022 staking mints", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    // creates a Token-2022 mint with the given extension and its initialize instruction
+    async function createMint2022(extension: ExtensionType, initExtensionIx: (mint: PublicKey) => any) {
+        const mintKeypair = Keypair.generate()
+        const mintLen = getMintLen([extension])
+        const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen)
+
+        const tx = new Transaction().add(
+            SystemProgram.createAccount({
+                fromPubkey: programAuthority.publicKey,
+                newAccountPubkey: mintKeypair.publicKey,
+                space: mintLen,
+                lamports,
+                programId: TOKEN_2022_PROGRAM_ID
+            }),
+            initExtensionIx(mintKeypair.publicKey),
+            createInitializeMintInstruction(mintKeypair.publicKey, 6, programAuthority.publicKey, null, TOKEN_2022_PROGRAM_ID)
+        )
+        await sendAndConfirmTransaction(provider.connection, tx, [programAuthority, mintKeypair])
+
+        return mintKeypair.publicKey
+    }
+
+    function poolAddresses(tokenMint: PublicKey) {
+        const [vaultAuthority] = PublicKey.findProgramAddressSync(
+            [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+            program.programId
+        )
+        const [pool] = PublicKey.findProgramAddressSync(
+            [tokenMint.toBuffer(), Buffer.from("state")],
+            program.programId
+        )
+        const [stakeVault] = PublicKey.findProgramAddressSync(
+            [tokenMint.toBuffer(), vaultAuthority.toBuffer(), Buffer.from("vault")],
+            program.programId
+        )
+        return { vaultAuthority, pool, stakeVault }
+    }
+
+    const initPool = (tokenMint: PublicKey) => {
+        const { vaultAuthority, pool, stakeVault } = poolAddresses(tokenMint)
+        return program.methods.initPool()
+            .accounts({
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                programAuthority: programAuthority.publicKey,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+    }
+
+    it("Stake credits what reaches the vault after transfer fees", async () => {
+        await safeAirdrop(programAuthority.publicKey, provider.connection)
+        await safeAirdrop(userKeypair1.publicKey, provider.connection)
+
+        const tokenMint = await createMint2022(ExtensionType.TransferFeeConfig, (mint) =>
+            createInitializeTransferFeeConfigInstruction(mint, programAuthority.publicKey, programAuthority.publicKey, FEE_BPS, BigInt(1000 * MULT), TOKEN_2022_PROGRAM_ID)
+        )
+        const { vaultAuthority, pool, stakeVault } = poolAddresses(tokenMint)
+
+        const userAta = await createAssociatedTokenAccount(provider.connection, userKeypair1, tokenMint, userKeypair1.publicKey, undefined, TOKEN_2022_PROGRAM_ID)
+        await mintTo(provider.connection, userKeypair1, tokenMint, userAta, programAuthority, 1000 * MULT, [], undefined, TOKEN_2022_PROGRAM_ID)
+        await setAuthority(provider.connection, programAuthority, tokenMint, programAuthority, AuthorityType.MintTokens, vaultAuthority, [], undefined, TOKEN_2022_PROGRAM_ID)
+
+        await initPool(tokenMint)
+
+        const [userEntry] = PublicKey.findProgramAddressSync(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        // 1% of the transfer is withheld by the mint
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(poolAcct.amount.toNumber() == 99 * MULT)
+        assert(poolAcct.userDepositAmt.toNumber() == 99 * MULT)
+        assert(userEntryAcct.balance.toNumber() == 99 * MULT)
+
+        await program.methods.unstake(new BN(99 * MULT))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_2022_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const drainedPoolAcct = await program.account.poolState.fetch(pool)
+        assert(drainedPoolAcct.amount.toNumber() == 0)
+    })
+
+    it("Pools can't be created for non-transferable mints", async () => {
+        const tokenMint = await createMint2022(ExtensionType.NonTransferable, (mint) =>
+            createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID)
+        )
+
+        try {
+            await initPool(tokenMint)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: InvalidMintExtension. Error Number: 6029. Error Message: Mint has a token extension pools don't support.")
+        }
+    })
+
+    it("Pools can't be created for mints that can be closed", async () => {
+        const tokenMint = await createMint2022(ExtensionType.MintCloseAuthority, (mint) =>
+            createInitializeMintCloseAuthorityInstruction(mint, programAuthority.publicKey, TOKEN_2022_PROGRAM_ID)
+        )
+
+        try {
+            await initPool(tokenMint)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: InvalidMintExtension. Error Number: 6029. Error Message: Mint has a token extension pools don't support.")
+        }
+    })
+})
\ No newline at end of file
@@ -0,0 +1,112 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount, transfer } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("reconcile vault", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let userEntry: PublicKey
+    let userAta: PublicKey
+
+    const reconcile = (correct: boolean, caller: Keypair) => program.methods.reconcile(correct)
+        .accounts({
+            caller: caller.publicKey,
+            poolState: pool,
+            tokenVault: stakeVault,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([caller])
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        userEntry = entry
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        // tokens sent straight to the vault never go through stake
+        await transfer(
+            program.provider.connection,
+            userKeypair1,
+            userAta,
+            stakeVault,
+            userKeypair1,
+            10 * MULT
+        )
+    })
+
+    it("Reporting drift leaves the books as they are", async () => {
+        await reconcile(false, userKeypair1)
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const vaultAcct = await getAccount(program.provider.connection, stakeVault)
+        assert(poolAcct.amount.toNumber() == 100 * MULT)
+        assert(Number(vaultAcct.amount) == 110 * MULT)
+    })
+
+    it("Only the pool authority can correct drift", async () => {
+        try {
+            await reconcile(true, userKeypair1)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+        }
+    })
+
+    it("Correcting a surplus distributes it to stakers", async () => {
+        await reconcile(true, programAuthority)
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const vaultAcct = await getAccount(program.provider.connection, stakeVault)
+        assert(poolAcct.amount.toNumber() == 110 * MULT)
+        assert(poolAcct.userDepositAmt.toNumber() == 100 * MULT)
+        assert(poolAcct.amount.add(poolAcct.boostReserve).add(poolAcct.pendingWithdrawal).toNumber() == Number(vaultAcct.amount))
+    })
+})
\ No newline at end of file
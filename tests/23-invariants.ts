@@ -0,0 +1,119 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { expect } from "chai"
+import { BN } from "bn.js"
+
+describe("invariant checks", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let entries: PublicKey[] = []
+
+    const checkInvariants = (poolState: PublicKey, tokenVault: PublicKey, stakeEntries: PublicKey[]) => program.methods.checkInvariants()
+        .accounts({
+            poolState: poolState,
+            tokenVault: tokenVault
+        })
+        .remainingAccounts(stakeEntries.map(entry => ({ pubkey: entry, isSigner: false, isWritable: false })))
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        for (const user of [userKeypair1, userKeypair2]) {
+            const [entry, entryBump] = await PublicKey.findProgramAddress(
+                [user.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+                program.programId
+            )
+            entries.push(entry)
+
+            await program.methods.initStakeEntry()
+                .accounts({
+                    user: user.publicKey,
+                    userStakeEntry: entry,
+                    poolState: pool,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+
+            await program.methods.stake(new BN(100 * MULT), new BN(0))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: user.publicKey,
+                    userStakeEntry: entry,
+                    userTokenAccount: await getAssociatedTokenAddress(tokenMint, user.publicKey),
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+        }
+
+        // distribute and burn check solvency on their way out
+        await program.methods.distribute(new BN(30 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        await program.methods.burn(new BN(20 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+    })
+
+    it("Pool and stake entries are covered by the vault", async () => {
+        await checkInvariants(pool, stakeVault, [])
+        await checkInvariants(pool, stakeVault, entries)
+
+        // passing an entry twice doesn't count it twice
+        await checkInvariants(pool, stakeVault, [entries[0], entries[0], entries[1]])
+    })
+
+    it("Stake entries of another pool are rejected", async () => {
+        const otherPool = await createTestPool(program, programAuthority)
+
+        try {
+            await checkInvariants(otherPool.pool, otherPool.stakeVault, entries)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: ConstraintSeeds. Error Number: 2006. Error Message: A seeds constraint was violated.")
+        }
+    })
+})
\ No newline at end of file
@@ -0,0 +1,122 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, Keypair, Transaction } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("stake for another user", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let beneficiaryEntry: PublicKey
+
+    // user 1 funds user 2's position
+    const stakeForBuilder = async (amount: number, lockDuration: number) => program.methods.stakeFor(new BN(amount), new BN(lockDuration))
+        .accounts({
+            pool: pool,
+            tokenVault: stakeVault,
+            funder: userKeypair1.publicKey,
+            beneficiary: userKeypair2.publicKey,
+            beneficiaryStakeEntry: beneficiaryEntry,
+            funderTokenAccount: await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey),
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+
+    const stakeFor = async (amount: number, lockDuration: number) => (await stakeForBuilder(amount, lockDuration))
+        .signers([userKeypair1])
+        .rpc()
+
+    const unstake = async (user: Keypair) => program.methods.unstake(new BN(10 * MULT))
+        .accounts({
+            pool: pool,
+            tokenVault: stakeVault,
+            user: user.publicKey,
+            userStakeEntry: beneficiaryEntry,
+            userTokenAccount: await getAssociatedTokenAddress(tokenMint, user.publicKey),
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            positionTokenAccount: program.programId,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([user])
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair2.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        beneficiaryEntry = entry
+    })
+
+    it("Stake for creates the beneficiary's entry", async () => {
+        await stakeFor(100 * MULT, 0)
+
+        const entryAcct = await program.account.stakeEntry.fetch(beneficiaryEntry)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(entryAcct.user.equals(userKeypair2.publicKey))
+        assert(entryAcct.balance.toNumber() == 100 * MULT)
+        assert(poolAcct.amount.toNumber() == 100 * MULT)
+        assert(poolAcct.userDepositAmt.toNumber() == 100 * MULT)
+
+        // a second deposit goes into the existing entry
+        await stakeFor(50 * MULT, 0)
+        const entryAcctAfter = await program.account.stakeEntry.fetch(beneficiaryEntry)
+        assert(entryAcctAfter.balance.toNumber() == 150 * MULT)
+    })
+
+    it("Funder can't lock the beneficiary's tokens", async () => {
+        try {
+            await stakeFor(10 * MULT, 86400)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: LockNotApproved. Error Number: 6033. Error Message: Locking a position needs the beneficiary's signature.")
+        }
+    })
+
+    it("Only the beneficiary can unstake", async () => {
+        try {
+            await unstake(userKeypair1)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: user. Error Code: InvalidUser. Error Number: 6005. Error Message: Invalid user provided.")
+        }
+
+        await unstake(userKeypair2)
+        const entryAcct = await program.account.stakeEntry.fetch(beneficiaryEntry)
+        assert(entryAcct.balance.toNumber() == 140 * MULT)
+    })
+
+    it("Beneficiary can co-sign a lock", async () => {
+        // the beneficiary isn't a signer in the idl, it opts in by signing the instruction anyway
+        const ix = await (await stakeForBuilder(10 * MULT, 86400)).instruction()
+        ix.keys.find((key) => key.pubkey.equals(userKeypair2.publicKey)).isSigner = true
+        await provider.sendAndConfirm(new Transaction().add(ix), [userKeypair1, userKeypair2])
+
+        const entryAcct = await program.account.stakeEntry.fetch(beneficiaryEntry)
+        assert(entryAcct.balance.toNumber() == 150 * MULT)
+        assert(entryAcct.lockEnd.toNumber() > 0)
+    })
+})
\ No newline at end of file
@@ -0,0 +1,146 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, Keypair } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress, createAssociatedTokenAccount, getAccount, getMint, transfer } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("transferable positions", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const positionMint = Keypair.generate()
+
+    let pool: PublicKey
+    let tokenMint: PublicKey
+    let stakeVault: PublicKey
+    let vaultAuthority: PublicKey
+    let positionEntry: PublicKey
+
+    const unstake = async (user: Keypair, positionTokenAccount: PublicKey) => program.methods.unstake(new BN(50 * MULT))
+        .accounts({
+            pool: pool,
+            tokenVault: stakeVault,
+            user: user.publicKey,
+            userStakeEntry: positionEntry,
+            userTokenAccount: await getAssociatedTokenAddress(tokenMint, user.publicKey),
+            vaultAuthority: vaultAuthority,
+            tokenMint: tokenMint,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            positionTokenAccount: positionTokenAccount,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([user])
+        .rpc()
+
+    before(async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [entry, entryBump] = await PublicKey.findProgramAddress(
+            [positionMint.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        positionEntry = entry
+    })
+
+    it("Init position entry mints a single position token", async () => {
+        const user1PositionAta = await getAssociatedTokenAddress(positionMint.publicKey, userKeypair1.publicKey)
+
+        await program.methods.initPositionEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                positionMint: positionMint.publicKey,
+                positionTokenAccount: user1PositionAta,
+                userStakeEntry: positionEntry,
+                poolState: pool,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1, positionMint])
+            .rpc()
+
+        const entryAcct = await program.account.stakeEntry.fetch(positionEntry)
+        assert(entryAcct.user.equals(positionMint.publicKey))
+        assert(entryAcct.positionMint.equals(positionMint.publicKey))
+
+        const mintAcct = await getMint(program.provider.connection, positionMint.publicKey)
+        assert(Number(mintAcct.supply) == 1)
+        assert(mintAcct.mintAuthority == null)
+
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: positionEntry,
+                userTokenAccount: await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey),
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: user1PositionAta,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const entryAcctAfter = await program.account.stakeEntry.fetch(positionEntry)
+        assert(entryAcctAfter.balance.toNumber() == 100 * MULT)
+    })
+
+    it("The position follows its token", async () => {
+        const user1PositionAta = await getAssociatedTokenAddress(positionMint.publicKey, userKeypair1.publicKey)
+        const user2PositionAta = await createAssociatedTokenAccount(
+            program.provider.connection,
+            userKeypair2,
+            positionMint.publicKey,
+            userKeypair2.publicKey
+        )
+        await transfer(
+            program.provider.connection,
+            userKeypair1,
+            user1PositionAta,
+            user2PositionAta,
+            userKeypair1,
+            1
+        )
+
+        // the previous holder lost the position along with the token
+        try {
+            await unstake(userKeypair1, user1PositionAta)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: user. Error Code: InvalidUser. Error Number: 6005. Error Message: Invalid user provided.")
+        }
+
+        const user2Ata = await getAssociatedTokenAddress(tokenMint, userKeypair2.publicKey)
+        const user2BalanceBefore = (await getAccount(program.provider.connection, user2Ata)).amount
+        await unstake(userKeypair2, user2PositionAta)
+
+        const entryAcct = await program.account.stakeEntry.fetch(positionEntry)
+        const user2BalanceAfter = (await getAccount(program.provider.connection, user2Ata)).amount
+        assert(entryAcct.balance.toNumber() == 50 * MULT)
+        assert(Number(user2BalanceAfter - user2BalanceBefore) == 50 * MULT)
+    })
+
+    it("Position entries can't be used without the token", async () => {
+        try {
+            await unstake(userKeypair2, program.programId)
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: user. Error Code: InvalidUser. Error Number: 6005. Error Message: Invalid user provided.")
+        }
+    })
+})
\ No newline at end of file
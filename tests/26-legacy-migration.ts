@@ -0,0 +1,223 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount, getMint } from '@solana/spl-token'
+import { eventAuthority, MULT, RATE_PRECISION, safeAirdrop } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+// The accounts in tests/fixtures are a pool as it was created at launch: vault and mint owned by the
+// program-wide "vault_authority" pda, pool state and stake entry in the launch layout with RATE_MULT
+// rates. userKeypair1 staked 1000, then 100 was distributed (rate 1.1 * RATE_MULT), and holds 500 more.
+describe("legacy pool migration", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const connection = program.provider.connection
+
+    const tokenMint = new PublicKey("4h5RJRKbShohWirwd6x7XS4kuL6rqbX95tQfTvWYyuaq")
+    const pool = new PublicKey("BX7JQniDNsLDPLqPJbBN8x521eBnU1yx6eyQ7Us96F88")
+    const legacyStakeVault = new PublicKey("G5TifTqMQMvoDtdQtLCq2Fx1ZZxo58UJPCmx7a7BNoi7")
+    const userEntry = new PublicKey("Gb9c9UqoWn4g2aBcENTrrAdJQeK1bgerZSphEYzv7hZt")
+
+    let legacyVaultAuthority: PublicKey
+    let vaultAuthority: PublicKey
+    let stakeVault: PublicKey
+    let userAta: PublicKey
+
+    const userAccounts = () => ({
+        pool: pool,
+        tokenVault: stakeVault,
+        user: userKeypair1.publicKey,
+        userStakeEntry: userEntry,
+        userTokenAccount: userAta,
+        vaultAuthority: vaultAuthority,
+        tokenMint: tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        positionTokenAccount: program.programId,
+        eventAuthority: eventAuthority(program),
+        program: program.programId
+    })
+
+    const upgradePool = () => program.methods.upgradePoolState()
+        .accounts({
+            programAuthority: programAuthority.publicKey,
+            poolState: pool,
+            tokenMint: tokenMint,
+            systemProgram: SystemProgram.programId
+        })
+        .signers([programAuthority])
+        .rpc()
+
+    before(async () => {
+        await safeAirdrop(programAuthority.publicKey, connection)
+        await safeAirdrop(userKeypair1.publicKey, connection)
+
+        ;[legacyVaultAuthority] = await PublicKey.findProgramAddress([Buffer.from("vault_authority")], program.programId)
+        ;[vaultAuthority] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+            program.programId
+        )
+        ;[stakeVault] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), vaultAuthority.toBuffer(), Buffer.from("vault")],
+            program.programId
+        )
+        userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+    })
+
+    it("Launch-layout pool can't be used before it's upgraded", async () => {
+        try {
+            await program.methods.stake(new BN(500 * MULT), new BN(0))
+                .accounts({ ...userAccounts(), tokenVault: legacyStakeVault, vaultAuthority: legacyVaultAuthority,
+ eventAuthority: eventAuthority(program),
+ program: program.programId })
+                .signers([userKeypair1])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pool. Error Code: AccountDidNotDeserialize. Error Number: 3003. Error Message: Failed to deserialize the account.")
+        }
+    })
+
+    it("Upgrade pool state", async () => {
+        await upgradePool()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.amount.toNumber() == 1100 * MULT)
+        assert(poolAcct.userDepositAmt.toNumber() == 1000 * MULT)
+        // 1.1 in RATE_MULT decimals is 1.1 in RATE_PRECISION decimals now
+        assert(poolAcct.distributionRate.eq(RATE_PRECISION.muln(11).divn(10)))
+        assert(poolAcct.vaultAuthority.toBase58() == legacyVaultAuthority.toBase58())
+        assert(poolAcct.distributor.toBase58() == programAuthority.publicKey.toBase58())
+        assert(poolAcct.epoch.toNumber() == 0)
+
+        const poolInfo = await connection.getAccountInfo(pool)
+        assert(poolInfo.data.length == program.account.poolState.size)
+    })
+
+    it("Pool state can only be upgraded once", async () => {
+        try {
+            await upgradePool()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: AccountAlreadyUpgraded. Error Number: 6031. Error Message: Account is already in the current layout.")
+        }
+    })
+
+    it("Upgrade stake entry", async () => {
+        await program.methods.upgradeStakeEntry()
+            .accounts({
+                payer: userKeypair1.publicKey,
+                poolState: pool,
+                userStakeEntry: userEntry,
+                systemProgram: SystemProgram.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const entryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(entryAcct.user.toBase58() == userKeypair1.publicKey.toBase58())
+        assert(entryAcct.balance.toNumber() == 1000 * MULT)
+        // the launch sentinel 1 stands for 1.0
+        assert(entryAcct.initialDistributionRate.eq(RATE_PRECISION))
+
+        const view = await program.methods.viewPosition()
+            .accounts({ pool: pool, userStakeEntry: userEntry })
+            .view()
+        assert(view.settledBalance.toNumber() == 1100 * MULT)
+    })
+
+    it("Migrate vault authority", async () => {
+        await program.methods.migrateVaultAuthority()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                legacyTokenVault: legacyStakeVault,
+                legacyVaultAuthority: legacyVaultAuthority,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.tokenVault.toBase58() == stakeVault.toBase58())
+        assert(poolAcct.vaultAuthority.toBase58() == vaultAuthority.toBase58())
+
+        const vaultAcct = await getAccount(connection, stakeVault)
+        assert(Number(vaultAcct.amount) == 1100 * MULT)
+        assert(await connection.getAccountInfo(legacyStakeVault) == null)
+
+        const mintAcct = await getMint(connection, tokenMint)
+        assert(mintAcct.mintAuthority.toBase58() == vaultAuthority.toBase58())
+    })
+
+    it("Stake, distribute, burn and unstake against the per-pool authority", async () => {
+        // settles to 1100 and adds 500
+        await program.methods.stake(new BN(500 * MULT), new BN(0))
+            .accounts(userAccounts())
+            .signers([userKeypair1])
+            .rpc()
+
+        let entryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(entryAcct.balance.toNumber() == 1600 * MULT)
+
+        // +10%
+        await program.methods.distribute(new BN(160 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        // -10%
+        await program.methods.burn(new BN(176 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const view = await program.methods.viewPosition()
+            .accounts({ pool: pool, userStakeEntry: userEntry })
+            .view()
+        assert(view.settledBalance.toNumber() == 1584 * MULT)
+
+        await program.methods.unstake(new BN(1584 * MULT))
+            .accounts(userAccounts())
+            .signers([userKeypair1])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        const vaultAcct = await getAccount(connection, stakeVault)
+        const userAcct = await getAccount(connection, userAta)
+        entryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(entryAcct.balance.toNumber() == 0)
+        assert(poolAcct.amount.toNumber() == 0)
+        assert(Number(vaultAcct.amount) == 0)
+        assert(Number(userAcct.amount) == 1584 * MULT)
+    })
+})
\ No newline at end of file
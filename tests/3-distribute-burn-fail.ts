@@ -3,7 +3,7 @@ import { Program } from "@project-serum/anchor";
 import { HedgeTakeHome } from "../target/types/hedge_take_home"
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from '@solana/web3.js'
 import { TOKEN_PROGRAM_ID, createMint, setAuthority, AuthorityType, getAssociatedTokenAddress } from '@solana/spl-token'
-import { delay, initializeTestUsers, safeAirdrop } from './utils/util'
+import { delay, initializeTestUsers, safeAirdrop, eventAuthority } from './utils/util'
 import { incorrectProgramAuthority, programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
 import { expect } from "chai"
 import { BN } from "bn.js"
@@ -19,10 +19,7 @@ describe("test persmissioned instructions", async () => {
     let stakeVault: PublicKey = null
     let pool: PublicKey = null
 
-    let [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
-        [Buffer.from("vault_authority")],
-        program.programId
-    )
+    let vaultAuthority: PublicKey = null
 
     it("Distribute and burn tokens with incorrect authority", async () => {
         await safeAirdrop(programAuthority.publicKey, provider.connection)
@@ -37,6 +34,13 @@ describe("test persmissioned instructions", async () => {
             9,
         )
         
+        // each pool has its own vault authority derived from the mint
+        const [vaultAuth, vaultAuthBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+            program.programId
+        )
+        vaultAuthority = vaultAuth
+
         // mint RND to test users
         await initializeTestUsers(provider.connection, tokenMint, programAuthority)
         
@@ -72,7 +76,9 @@ describe("test persmissioned instructions", async () => {
                 vaultAuthority: vaultAuthority,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
-                rent: SYSVAR_RENT_PUBKEY
+                rent: SYSVAR_RENT_PUBKEY,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .signers([programAuthority])
                 .rpc()
@@ -86,22 +92,29 @@ describe("test persmissioned instructions", async () => {
                 .accounts({
                 user: userKeypair1.publicKey,
                 userStakeEntry: user1Entry,
-                poolState: pool
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .signers([userKeypair1])
                 .rpc()
 
             const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
             
-            await program.methods.stake(new BN(100 * LAMPORTS_PER_SOL))
+            await program.methods.stake(new BN(100 * LAMPORTS_PER_SOL), new BN(0))
                 .accounts({
                     pool: pool,
                     tokenVault: stakeVault,
                     user: userKeypair1.publicKey,
                     userStakeEntry: user1Entry,
                     userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
                     tokenProgram: TOKEN_PROGRAM_ID,
-                    systemProgram: SystemProgram.programId
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
                 })
                 .signers([userKeypair1])
                 .rpc()
@@ -118,7 +131,9 @@ describe("test persmissioned instructions", async () => {
                 tokenVault: stakeVault,
                 tokenMint: tokenMint,
                 mintAuth: vaultAuthority,
-                tokenProgram: TOKEN_PROGRAM_ID
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .signers([incorrectProgramAuthority])
                 .rpc()
@@ -137,7 +152,9 @@ describe("test persmissioned instructions", async () => {
                 tokenVault: stakeVault,
                 tokenMint: tokenMint,
                 mintAuth: vaultAuthority,
-                tokenProgram: TOKEN_PROGRAM_ID
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .rpc()
             expect(true, "promise should fail").eq(false)
@@ -155,7 +172,9 @@ describe("test persmissioned instructions", async () => {
                 tokenVault: stakeVault,
                 tokenMint: tokenMint,
                 vaultAuthority: vaultAuthority,
-                tokenProgram: TOKEN_PROGRAM_ID
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .signers([incorrectProgramAuthority])
                 .rpc()
@@ -174,7 +193,9 @@ describe("test persmissioned instructions", async () => {
                 tokenVault: stakeVault,
                 tokenMint: tokenMint,
                 vaultAuthority: vaultAuthority,
-                tokenProgram: TOKEN_PROGRAM_ID
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
                 })
                 .rpc()
             expect(true, "promise should fail").eq(false)
@@ -183,4 +204,26 @@ describe("test persmissioned instructions", async () => {
             expect(e.message).to.eq("Signature verification failed")
         }
     })
+
+    it("Burn more tokens than are staked", async () => {
+        try {
+            await program.methods.burn(new BN(25 * LAMPORTS_PER_SOL))
+                .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError occurred. Error Code: BurnExceedsPool. Error Number: 6021. Error Message: Attempted to burn more tokens than are staked in the pool.")
+        }
+    })
 })
\ No newline at end of file
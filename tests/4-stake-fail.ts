@@ -3,7 +3,7 @@ import { Program } from "@project-serum/anchor";
 import { HedgeTakeHome } from "../target/types/hedge_take_home"
 import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL } from '@solana/web3.js'
 import { TOKEN_PROGRAM_ID, createMint, setAuthority, AuthorityType, getAssociatedTokenAddress } from '@solana/spl-token'
-import { delay, initializeTestUsers, safeAirdrop } from './utils/util'
+import { delay, initializeTestUsers, safeAirdrop, eventAuthority } from './utils/util'
 import { incorrectProgramAuthority, programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
 import { expect } from "chai"
 import { BN } from "bn.js"
@@ -19,10 +19,7 @@ describe("test staking tokens without ownership", async () => {
     let stakeVault: PublicKey = null
     let pool: PublicKey = null
 
-    let [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
-        [Buffer.from("vault_authority")],
-        program.programId
-    )
+    let vaultAuthority: PublicKey = null
 
     it("Stake tokens from account user is not authority", async () => {
         await safeAirdrop(programAuthority.publicKey, provider.connection)
@@ -37,6 +34,13 @@ describe("test staking tokens without ownership", async () => {
             9,
         )
         
+        // each pool has its own vault authority derived from the mint
+        const [vaultAuth, vaultAuthBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+            program.programId
+        )
+        vaultAuthority = vaultAuth
+
         // mint RND to test users
         await initializeTestUsers(provider.connection, tokenMint, programAuthority)
         
@@ -71,7 +75,9 @@ describe("test staking tokens without ownership", async () => {
             vaultAuthority: vaultAuthority,
             tokenProgram: TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
-            rent: SYSVAR_RENT_PUBKEY
+            rent: SYSVAR_RENT_PUBKEY,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
             })
             .signers([programAuthority])
             .rpc()
@@ -85,7 +91,9 @@ describe("test staking tokens without ownership", async () => {
             .accounts({
             user: userKeypair1.publicKey,
             userStakeEntry: user1Entry,
-            poolState: pool
+            poolState: pool,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
             })
             .signers([userKeypair1])
             .rpc()
@@ -94,15 +102,20 @@ describe("test staking tokens without ownership", async () => {
 
         // try to stake tokens without owner's authority
         try {
-            await program.methods.stake(new BN(100 * LAMPORTS_PER_SOL))
+            await program.methods.stake(new BN(100 * LAMPORTS_PER_SOL), new BN(0))
                 .accounts({
                     pool: pool,
                     tokenVault: stakeVault,
                     user: userKeypair2.publicKey,
                     userStakeEntry: user1Entry,
                     userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
                     tokenProgram: TOKEN_PROGRAM_ID,
-                    systemProgram: SystemProgram.programId
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
                 })
                 .signers([userKeypair2])
                 .rpc()
@@ -0,0 +1,174 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, createMint, setAuthority, AuthorityType, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { delay, initializeTestUsers, safeAirdrop, settledBalance, eventAuthority } from './utils/util'
+import { programAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("stake, distribute, burn and unstake for different mint decimals", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    for (const decimals of [0, 6, 9, 18]) {
+        it(`Full staking flow with a ${decimals} decimal mint`, async () => {
+            await safeAirdrop(programAuthority.publicKey, provider.connection)
+            delay(10000)
+
+            // create RND mint
+            const tokenMint = await createMint(
+                provider.connection,
+                programAuthority,
+                programAuthority.publicKey,
+                programAuthority.publicKey,
+                decimals,
+            )
+
+            const [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
+                [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+                program.programId
+            )
+
+            // mint RND to test users
+            await initializeTestUsers(provider.connection, tokenMint, programAuthority)
+
+            // assign RND mint to a PDA of the staking program
+            await setAuthority(
+                provider.connection,
+                programAuthority,
+                tokenMint,
+                programAuthority,
+                AuthorityType.MintTokens,
+                vaultAuthority
+            )
+
+            const [pool, poolBump] = await PublicKey.findProgramAddress(
+                [tokenMint.toBuffer(), Buffer.from("state")],
+                program.programId
+            )
+
+            const [stakeVault, vaultBump] = await PublicKey.findProgramAddress(
+                [tokenMint.toBuffer(), vaultAuthority.toBuffer(), Buffer.from("vault")],
+                program.programId
+            )
+
+            await program.methods.initPool()
+                .accounts({
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    tokenMint: tokenMint,
+                    programAuthority: programAuthority.publicKey,
+                    vaultAuthority: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    rent: SYSVAR_RENT_PUBKEY,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+
+            const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+                [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+                program.programId
+            )
+
+            await program.methods.initStakeEntry()
+                .accounts({
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    poolState: pool,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+
+            const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+
+            // amounts are in base units so they fit in a u64 for every decimals value
+            await program.methods.stake(new BN(100_000), new BN(0))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+
+            await program.methods.distribute(new BN(10_000))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    tokenMint: tokenMint,
+                    mintAuth: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+
+            await program.methods.burn(new BN(5_000))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+
+            let userTokenAcct = await getAccount(provider.connection, userAta)
+            const initialUserBalance = userTokenAcct.amount
+
+            const poolAcct = await program.account.poolState.fetch(pool)
+            const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+            const expectedAmt = settledBalance(poolAcct, userEntryAcct)
+
+            await program.methods.unstake(expectedAmt)
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: userKeypair1.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([userKeypair1])
+                .rpc()
+
+            userTokenAcct = await getAccount(provider.connection, userAta)
+            assert(userTokenAcct.amount == initialUserBalance + BigInt(expectedAmt.toString()))
+
+            const stakeVaultAcct = await getAccount(provider.connection, stakeVault)
+            const updatedPoolAcct = await program.account.poolState.fetch(pool)
+            assert(Number(stakeVaultAcct.amount) == updatedPoolAcct.amount.toNumber())
+            assert(updatedPoolAcct.amount.toNumber() == 0)
+        })
+    }
+})
\ No newline at end of file
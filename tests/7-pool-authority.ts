@@ -0,0 +1,105 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token'
+import { createTestPool, eventAuthority } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("pool authority handover", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+
+    it("Create stake pool", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.authority.toBase58() == programAuthority.publicKey.toBase58())
+        assert(poolAcct.pendingAuthority.toBase58() == PublicKey.default.toBase58())
+    })
+
+    it("Only the pending authority can accept", async () => {
+        await program.methods.setPoolAuthority(userKeypair1.publicKey)
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.authority.toBase58() == programAuthority.publicKey.toBase58())
+        assert(poolAcct.pendingAuthority.toBase58() == userKeypair1.publicKey.toBase58())
+
+        try {
+            await program.methods.acceptPoolAuthority()
+                .accounts({
+                    pendingAuthority: userKeypair2.publicKey,
+                    poolState: pool
+                })
+                .signers([userKeypair2])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: pending_authority. Error Code: InvalidPendingAuthority. Error Number: 6007. Error Message: Signer is not the pending pool authority.")
+        }
+    })
+
+    it("New authority accepts and old authority loses access", async () => {
+        await program.methods.acceptPoolAuthority()
+            .accounts({
+                pendingAuthority: userKeypair1.publicKey,
+                poolState: pool
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.authority.toBase58() == userKeypair1.publicKey.toBase58())
+        assert(poolAcct.pendingAuthority.toBase58() == PublicKey.default.toBase58())
+
+        try {
+            await program.methods.distribute(new BN(1))
+                .accounts({
+                    programAuthority: programAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    tokenMint: tokenMint,
+                    mintAuth: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([programAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+        }
+
+        // new authority can distribute
+        await program.methods.distribute(new BN(1))
+            .accounts({
+                programAuthority: userKeypair1.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                tokenMint: tokenMint,
+                mintAuth: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    })
+})
\ No newline at end of file
@@ -0,0 +1,166 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, delay, eventAuthority, MULT } from './utils/util'
+import { programAuthority, incorrectProgramAuthority, userKeypair1 } from './testKeypairs/testKeypairs'
+import { assert, expect } from "chai"
+import { BN } from "bn.js"
+
+describe("time based reward streaming", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+
+    it("Stake into a new pool", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+
+        await program.methods.initStakeEntry()
+            .accounts({
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                poolState: pool,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+        await program.methods.stake(new BN(100 * MULT), new BN(0))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+    })
+
+    it("Only the pool authority can set emissions", async () => {
+        try {
+            await program.methods.setEmissions(new BN(MULT), new BN(0), new BN(1))
+                .accounts({
+                    programAuthority: incorrectProgramAuthority.publicKey,
+                    poolState: pool,
+                    tokenVault: stakeVault,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID
+                })
+                .signers([incorrectProgramAuthority])
+                .rpc()
+            expect(true, "promise should fail").eq(false)
+        } catch (e) {
+            console.log(e.message)
+            expect(e.message).to.eq("AnchorError caused by account: program_authority. Error Code: InvalidProgramAuthority. Error Number: 6003. Error Message: Incorrect program authority.")
+        }
+    })
+
+    it("Streamed rewards accrue on crank", async () => {
+        const now = await provider.connection.getBlockTime(await provider.connection.getSlot())
+
+        await program.methods.setEmissions(new BN(MULT), new BN(now), new BN(now + 3600))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        let poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.emissionRate.toNumber() == MULT)
+        const initialPoolAmt = poolAcct.amount.toNumber()
+        const initialDistributionRate = poolAcct.distributionRate
+
+        await delay(3000)
+
+        await program.methods.crankEmissions()
+            .accounts({
+                poolState: pool,
+                tokenVault: stakeVault,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID
+            })
+            .rpc()
+
+        poolAcct = await program.account.poolState.fetch(pool)
+        const streamed = poolAcct.amount.toNumber() - initialPoolAmt
+        console.log("Streamed rewards: ", streamed)
+        assert(streamed > 0)
+        assert(streamed % MULT == 0)
+        assert(poolAcct.distributionRate.gt(initialDistributionRate))
+
+        const vaultAcct = await getAccount(provider.connection, stakeVault)
+        assert(Number(vaultAcct.amount) == poolAcct.amount.toNumber())
+    })
+
+    it("Full exit takes the whole settled balance while rewards stream", async () => {
+        const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+            [userKeypair1.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+            program.programId
+        )
+        const userAta = await getAssociatedTokenAddress(tokenMint, userKeypair1.publicKey)
+        const initialUserAmt = Number((await getAccount(provider.connection, userAta)).amount)
+        const initialPoolAcct = await program.account.poolState.fetch(pool)
+        assert(initialPoolAcct.emissionRate.toNumber() == MULT)
+
+        // rewards keep streaming until the transaction lands, no amount quoted beforehand would empty the entry
+        await delay(2000)
+
+        await program.methods.unstake(new BN("18446744073709551615"))
+            .accounts({
+                pool: pool,
+                tokenVault: stakeVault,
+                user: userKeypair1.publicKey,
+                userStakeEntry: userEntry,
+                userTokenAccount: userAta,
+                vaultAuthority: vaultAuthority,
+                tokenMint: tokenMint,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                positionTokenAccount: program.programId,
+                eventAuthority: eventAuthority(program),
+                program: program.programId
+            })
+            .signers([userKeypair1])
+            .rpc()
+
+        const userEntryAcct = await program.account.stakeEntry.fetch(userEntry)
+        assert(userEntryAcct.balance.toNumber() == 0)
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.userDepositAmt.toNumber() == 0)
+
+        // the only staker takes everything staked, including what streamed in the same transaction
+        const received = Number((await getAccount(provider.connection, userAta)).amount) - initialUserAmt
+        console.log("Received on full exit: ", received)
+        assert(received > initialPoolAcct.amount.toNumber())
+        assert(poolAcct.amount.toNumber() < 10)
+    })
+})
\ No newline at end of file
@@ -0,0 +1,167 @@
+import * as anchor from "@project-serum/anchor";
+import { Program } from "@project-serum/anchor";
+import { HedgeTakeHome } from "../target/types/hedge_take_home"
+import { PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID, createMint, createAssociatedTokenAccount, mintTo, getAssociatedTokenAddress, getAccount } from '@solana/spl-token'
+import { createTestPool, eventAuthority, MULT } from './utils/util'
+import { programAuthority, userKeypair1, userKeypair2 } from './testKeypairs/testKeypairs'
+import { assert } from "chai"
+import { BN } from "bn.js"
+
+describe("partner reward mints", async () => {
+    anchor.setProvider(anchor.AnchorProvider.env())
+
+    const program = anchor.workspace.HedgeTakeHome as Program<HedgeTakeHome>
+    const provider = anchor.AnchorProvider.env()
+
+    let tokenMint: PublicKey = null
+    let vaultAuthority: PublicKey = null
+    let pool: PublicKey = null
+    let stakeVault: PublicKey = null
+    let rewardMint: PublicKey = null
+    let rewardVault: PublicKey = null
+    const userEntries: PublicKey[] = []
+
+    it("Add a partner reward mint to a new pool", async () => {
+        ({ tokenMint, vaultAuthority, pool, stakeVault } = await createTestPool(program, programAuthority))
+
+        rewardMint = await createMint(
+            provider.connection,
+            programAuthority,
+            programAuthority.publicKey,
+            programAuthority.publicKey,
+            6,
+        )
+
+        const [vault, vaultBump] = await PublicKey.findProgramAddress(
+            [tokenMint.toBuffer(), rewardMint.toBuffer(), Buffer.from("reward_vault")],
+            program.programId
+        )
+        rewardVault = vault
+
+        await program.methods.addRewardMint()
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                vaultAuthority: vaultAuthority,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                systemProgram: SystemProgram.programId,
+                rent: SYSVAR_RENT_PUBKEY
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const poolAcct = await program.account.poolState.fetch(pool)
+        assert(poolAcct.rewardInfos[0].mint.toBase58() == rewardMint.toBase58())
+        assert(poolAcct.rewardInfos[0].vault.toBase58() == rewardVault.toBase58())
+        assert(poolAcct.rewardInfos[1].mint.toBase58() == PublicKey.default.toBase58())
+    })
+
+    it("Users stake RND", async () => {
+        for (const [user, amount] of [[userKeypair1, 100], [userKeypair2, 300]] as [anchor.web3.Keypair, number][]) {
+            const [userEntry, entryBump] = await PublicKey.findProgramAddress(
+                [user.publicKey.toBuffer(), tokenMint.toBuffer(), Buffer.from("stake_entry")],
+                program.programId
+            )
+            userEntries.push(userEntry)
+
+            await program.methods.initStakeEntry()
+                .accounts({
+                    user: user.publicKey,
+                    userStakeEntry: userEntry,
+                    poolState: pool,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+
+            const userAta = await getAssociatedTokenAddress(tokenMint, user.publicKey)
+            await program.methods.stake(new BN(amount * MULT), new BN(0))
+                .accounts({
+                    pool: pool,
+                    tokenVault: stakeVault,
+                    user: user.publicKey,
+                    userStakeEntry: userEntry,
+                    userTokenAccount: userAta,
+                    vaultAuthority: vaultAuthority,
+                    tokenMint: tokenMint,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    systemProgram: SystemProgram.programId,
+                    positionTokenAccount: program.programId,
+                    eventAuthority: eventAuthority(program),
+                    program: program.programId
+                })
+                .signers([user])
+                .rpc()
+        }
+    })
+
+    it("Partner rewards are shared by stake weight", async () => {
+        const funderAta = await createAssociatedTokenAccount(
+            provider.connection,
+            programAuthority,
+            rewardMint,
+            programAuthority.publicKey
+        )
+        await mintTo(
+            provider.connection,
+            programAuthority,
+            rewardMint,
+            funderAta,
+            programAuthority,
+            400 * MULT
+        )
+
+        await program.methods.distributeReward(new BN(400 * MULT))
+            .accounts({
+                programAuthority: programAuthority.publicKey,
+                poolState: pool,
+                rewardMint: rewardMint,
+                rewardVault: rewardVault,
+                funderTokenAccount: funderAta,
+                tokenProgram: TOKEN_PROGRAM_ID
+            })
+            .signers([programAuthority])
+            .rpc()
+
+        const vaultAcct = await getAccount(provider.connection, rewardVault)
+        assert(Number(vaultAcct.amount) == 400 * MULT)
+
+        const users = [userKeypair1, userKeypair2]
+        const expected = [100 * MULT, 300 * MULT]
+        for (let i = 0; i < users.length; i++) {
+            const userRewardAta = await createAssociatedTokenAccount(
+                provider.connection,
+                users[i],
+                rewardMint,
+                users[i].publicKey
+            )
+
+            await program.methods.claimRewards()
+                .accounts({
+                    pool: pool,
+                    user: users[i].publicKey,
+                    userStakeEntry: userEntries[i],
+                    vaultAuthority: vaultAuthority,
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    positionTokenAccount: program.programId
+                })
+                .remainingAccounts([
+                    { pubkey: rewardVault, isWritable: true, isSigner: false },
+                    { pubkey: rewardMint, isWritable: false, isSigner: false },
+                    { pubkey: userRewardAta, isWritable: true, isSigner: false }
+                ])
+                .signers([users[i]])
+                .rpc()
+
+            const userRewardAcct = await getAccount(provider.connection, userRewardAta)
+            assert(Number(userRewardAcct.amount) == expected[i])
+
+            const userEntryAcct = await program.account.stakeEntry.fetch(userEntries[i])
+            assert(userEntryAcct.rewardsOwed[0].toNumber() == 0)
+        }
+    })
+})
\ No newline at end of file
@@ -1,14 +1,134 @@
 import { userKeypair1, userKeypair2, userKeypair3 } from '../testKeypairs/testKeypairs'
 import { PublicKey, Keypair, SystemProgram, SYSVAR_RENT_PUBKEY, LAMPORTS_PER_SOL, Connection } from '@solana/web3.js'
-import { TOKEN_PROGRAM_ID, mintTo, createAssociatedTokenAccount } from '@solana/spl-token'
+import { TOKEN_PROGRAM_ID, mintTo, createAssociatedTokenAccount, createMint, setAuthority, AuthorityType } from '@solana/spl-token'
+import { Program } from "@project-serum/anchor"
+import { HedgeTakeHome } from "../../target/types/hedge_take_home"
+import { BN } from "bn.js"
 
 export const MULT: number = 1_000_000
-export const RATE_MULT: number = 100_000_000_000
+export const RATE_PRECISION: BN = new BN("1000000000000000000")
+export const SCALE_FACTOR: BN = new BN(1_000_000_000)
+
+// mirrors accumulated_earnings in the program
+function accumulatedEarnings(weight: BN, current: BN, prevScaleTotal: BN, checkpoint: BN, scaleDiff: number, denominator: BN): BN {
+    if (scaleDiff == 0) {
+        return weight.mul(current.sub(checkpoint)).div(denominator)
+    } else if (scaleDiff == 1) {
+        return weight.mul(prevScaleTotal.sub(checkpoint)).div(denominator)
+            .add(weight.mul(current).div(denominator.mul(SCALE_FACTOR)))
+    }
+    return new BN(0)
+}
+
+// mirrors settled_balance in the program, from fetched accounts and without streamed rewards not yet cranked
+export function settledBalance(poolAcct: any, entryAcct: any): BN {
+    const initialRate: BN = entryAcct.initialDistributionRate
+    const boostWeight = entryAcct.boostWeight.mul(RATE_PRECISION).div(initialRate)
+
+    // positions from before a wipe settle to zero, keeping the boost they earned up to it
+    if (!entryAcct.initialEpoch.eq(poolAcct.epoch)) {
+        if (entryAcct.initialEpoch.addn(1).eq(poolAcct.epoch) && entryAcct.initialRateScale.eq(poolAcct.epochEndRateScale)) {
+            return accumulatedEarnings(boostWeight, poolAcct.epochEndBoostRate, new BN(0), entryAcct.boostCheckpoint, 0, RATE_PRECISION)
+        }
+        return new BN(0)
+    }
+
+    const scaleDiff = poolAcct.rateScale.sub(entryAcct.initialRateScale).toNumber()
+
+    let balance = new BN(0)
+    if (scaleDiff == 0) {
+        balance = entryAcct.balance.mul(poolAcct.distributionRate).div(initialRate)
+    } else if (scaleDiff == 1) {
+        balance = entryAcct.balance.mul(poolAcct.distributionRate).div(initialRate.mul(SCALE_FACTOR))
+    }
+
+    // extra rewards earned by locked positions
+    const boostEarnings = accumulatedEarnings(boostWeight, poolAcct.boostRate, poolAcct.prevScaleBoostRate,
+        entryAcct.boostCheckpoint, scaleDiff, RATE_PRECISION)
+
+    return balance.add(boostEarnings)
+}
+
+// what unstake would pay the entry right now, quoted by the program itself through view_position
+export async function viewSettledBalance(program: Program<HedgeTakeHome>, pool: PublicKey, userStakeEntry: PublicKey): Promise<BN> {
+    const view = await program.methods.viewPosition()
+        .accounts({
+            pool: pool,
+            userStakeEntry: userStakeEntry
+        })
+        .view()
+
+    return view.settledBalance
+}
+
+// signs anchor's event-cpi self-CPI, every instruction that emits events takes it along with the program
+export function eventAuthority(program: Program<HedgeTakeHome>): PublicKey {
+    return PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId)[0]
+}
 
 export function delay(ms: number) {
     return new Promise( resolve => setTimeout(resolve, ms) );
 }
 
+// creates a fresh RND mint, funds the test users and initializes a pool for it
+export async function createTestPool(program: Program<HedgeTakeHome>, authority: Keypair, decimals: number = 6) {
+    const connection = program.provider.connection
+    await safeAirdrop(authority.publicKey, connection)
+
+    const tokenMint = await createMint(
+        connection,
+        authority,
+        authority.publicKey,
+        authority.publicKey,
+        decimals,
+    )
+
+    const [vaultAuthority, vaultAuthBump] = await PublicKey.findProgramAddress(
+        [tokenMint.toBuffer(), Buffer.from("vault_authority")],
+        program.programId
+    )
+
+    await initializeTestUsers(connection, tokenMint, authority)
+
+    // assign RND mint to a PDA of the staking program
+    await setAuthority(
+        connection,
+        authority,
+        tokenMint,
+        authority,
+        AuthorityType.MintTokens,
+        vaultAuthority
+    )
+
+    const [pool, poolBump] = await PublicKey.findProgramAddress(
+        [tokenMint.toBuffer(), Buffer.from("state")],
+        program.programId
+    )
+
+    const [stakeVault, vaultBump] = await PublicKey.findProgramAddress(
+        [tokenMint.toBuffer(), vaultAuthority.toBuffer(), Buffer.from("vault")],
+        program.programId
+    )
+
+    await program.methods.initPool()
+        .accounts({
+            poolState: pool,
+            tokenVault: stakeVault,
+            tokenMint: tokenMint,
+            programAuthority: authority.publicKey,
+            vaultAuthority: vaultAuthority,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+            eventAuthority: eventAuthority(program),
+            program: program.programId
+        })
+        .signers([authority])
+        .rpc()
+
+    return { tokenMint, vaultAuthority, pool, stakeVault }
+}
+
 export async function initializeTestUsers(connection: Connection, mint: PublicKey, mintAuthority: Keypair) {
     await safeAirdrop(userKeypair1.publicKey, connection)
     delay(10000)